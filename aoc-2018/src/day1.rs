@@ -1,50 +1,117 @@
-use std::collections::HashSet;
+use aoc_common::{find_cycle, parse_number, Answer, ParseError, Solution, SolveError};
+use std::iter;
 
-fn freq(nums: &[&str]) -> i32 {
-    nums.iter().map(|n| n.parse::<i32>().unwrap()).sum()
+fn parse_freqs(nums: &[&str]) -> Result<Vec<i32>, ParseError> {
+    if nums.is_empty() {
+        return Err(ParseError::new("a frequency change", "").at_line(1));
+    }
+    nums.iter()
+        .enumerate()
+        .map(|(i, n)| parse_number(n).map_err(|err| err.at_line(i + 1)))
+        .collect()
 }
 
-fn freq_2(nums: &[&str]) -> i32 {
-    let freqs: Vec<_> = nums.iter().map(|n| n.parse::<i32>().unwrap()).collect();
-
-    let mut sum = 0;
-    let mut seen = HashSet::new();
+fn freq(nums: &[&str]) -> Result<i32, ParseError> {
+    Ok(parse_freqs(nums)?.iter().sum())
+}
 
-    loop {
-        for freq in &freqs {
-            if seen.contains(&sum) {
-                return sum;
-            }
-            seen.insert(sum);
-            sum += freq;
+/// Returns the first running sum reached twice, applying the changes over
+/// and over. Fails if no sum is ever reached twice.
+fn freq_2(nums: &[&str]) -> Result<i32, ParseError> {
+    let freqs = parse_freqs(nums)?;
+    let sums = || {
+        iter::once(0).chain(freqs.iter().cycle().scan(0, |sum, freq| {
+            *sum += freq;
+            Some(*sum)
+        }))
+    };
+    // Each pass shifts every sum by the total change, so a sum can only be
+    // reached again within as many passes as it takes the total to cover
+    // the spread of the first pass
+    let first: Vec<i64> = sums().take(freqs.len()).map(i64::from).collect();
+    let total = freqs.iter().map(|freq| i64::from(*freq)).sum::<i64>();
+    let passes = match total {
+        0 => 1,
+        _ => {
+            let spread = first.iter().max().unwrap() - first.iter().min().unwrap();
+            spread / total.abs() + 2
         }
+    };
+    let len = freqs.len() * passes as usize + 1;
+    let cycle = find_cycle(sums().take(len))
+        .ok_or_else(|| ParseError::new("changes which reach a frequency twice", ""))?;
+    Ok(sums().nth(cycle.prefix).unwrap())
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        let nums: Vec<_> = input.lines().collect();
+        parse_freqs(&nums).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        let nums: Vec<_> = input.lines().collect();
+        Ok(freq(&nums)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        let nums: Vec<_> = input.lines().collect();
+        Ok(freq_2(&nums)?.into())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::INPUT;
 
     #[test]
     fn test_freq() {
         let nums = ["+1", "+1", "+1"];
-        assert_eq!(freq(&nums), 3);
+        assert_eq!(freq(&nums), Ok(3));
 
-        let input = include_str!("1.input");
+        let input = crate::input(1, INPUT);
         let nums: Vec<_> = input.lines().collect();
-        assert_eq!(freq(&nums), 543);
+        assert_eq!(freq(&nums), Ok(543));
+    }
+
+    #[test]
+    fn test_parse_freqs() {
+        let nums = ["+1", "-2", "3"];
+        assert_eq!(parse_freqs(&nums), Ok(vec![1, -2, 3]));
+
+        let nums = ["+1", "+ 2"];
+        let expected = ParseError::new("a number", "+ 2").at_line(2);
+        assert_eq!(freq(&nums), Err(expected));
+
+        let expected = ParseError::new("a frequency change", "").at_line(1);
+        assert_eq!(parse_freqs(&[]), Err(expected));
     }
 
     #[test]
     fn test_freq_2() {
         let nums = ["+1", "-1"];
-        assert_eq!(freq_2(&nums), 0);
+        assert_eq!(freq_2(&nums), Ok(0));
 
         let nums = ["+3", "+3", "+4", "-2", "-4"];
-        assert_eq!(freq_2(&nums), 10);
+        assert_eq!(freq_2(&nums), Ok(10));
+
+        let tests: &[&[&str]] = &[
+            &["-6", "+3", "+8", "+5", "-6"],
+            &["+7", "+7", "-2", "-7", "-4"],
+        ];
+        for (nums, expected) in tests.iter().zip(&[5, 14]) {
+            assert_eq!(freq_2(nums), Ok(*expected));
+        }
+
+        let expected = ParseError::new("changes which reach a frequency twice", "");
+        assert_eq!(freq_2(&["+1"]), Err(expected.clone()));
+        assert_eq!(freq_2(&["+5", "-2"]), Err(expected));
 
-        let input = include_str!("1.input");
+        let input = crate::input(1, INPUT);
         let nums: Vec<_> = input.lines().collect();
-        assert_eq!(freq_2(&nums), 621);
+        assert_eq!(freq_2(&nums), Ok(621));
     }
 }
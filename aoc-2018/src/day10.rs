@@ -1,3 +1,7 @@
+use aoc_common::{
+    ocr_str, parse_lines, parse_with, Answer, Color, Drawing, ParseError, Parser, Solution,
+    SolveError, SparseGrid,
+};
 use std::fmt;
 
 #[derive(Debug)]
@@ -5,28 +9,21 @@ struct Graph(Vec<Point>);
 
 impl fmt::Display for Graph {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let points: Vec<_> = self.0.iter().map(|p| p.position).collect();
-
-        let min_x = points.iter().map(|p| p.0).min().unwrap();
-        let max_x = points.iter().map(|p| p.0).max().unwrap();
-        let min_y = points.iter().map(|p| p.1).min().unwrap();
-        let max_y = points.iter().map(|p| p.1).max().unwrap();
-
-        for y in min_y..=max_y {
-            for x in min_x..=max_x {
-                if points.contains(&(x, y)) {
-                    write!(f, "#")?
-                } else {
-                    write!(f, ".")?
-                }
-            }
-            write!(f, "\n")?
-        }
-        Ok(())
+        let grid = self.to_sparse_grid();
+        writeln!(f, "{}", grid.render(|on| if *on { '#' } else { '.' }))
     }
 }
 
 impl Graph {
+    fn to_sparse_grid(&self) -> SparseGrid<bool> {
+        let mut grid = SparseGrid::new(false);
+        for point in &self.0 {
+            let (x, y) = point.position;
+            grid.insert(aoc_common::Point(x.into(), y.into()), true);
+        }
+        grid
+    }
+
     fn advance(&mut self) {
         for point in &mut self.0 {
             let x = point.position.0 + point.velocity.0;
@@ -65,42 +62,53 @@ struct Point {
 }
 
 fn print_graph(graph: &Graph) {
-    let points: Vec<_> = graph.0.iter().map(|p| p.position).collect();
-
-    let min_x = points.iter().map(|p| p.0).min().unwrap();
-    let max_x = points.iter().map(|p| p.0).max().unwrap();
-    let min_y = points.iter().map(|p| p.1).min().unwrap();
-    let max_y = points.iter().map(|p| p.1).max().unwrap();
+    print!("{}", graph);
+}
 
-    for y in min_y..=max_y {
-        for x in min_x..=max_x {
-            if points.contains(&(x, y)) {
-                print!("#");
-            } else {
-                print!(".");
-            }
-        }
-        println!("");
-    }
+/// Parses a pair like "< 9,  1>", which may be padded with spaces
+fn pair(p: &mut Parser) -> Result<(i32, i32), ParseError> {
+    p.literal("<")?;
+    p.whitespace();
+    let x = p.signed()?;
+    p.literal(",")?;
+    p.whitespace();
+    let y = p.signed()?;
+    p.literal(">")?;
+    Ok((x, y))
 }
 
-fn delim(c: char) -> bool {
-    c == '<' || c == ',' || c == '>'
+/// Parses a line like "position=< 9,  1> velocity=< 0,  2>"
+fn parse_line(s: &str) -> Result<Point, ParseError> {
+    parse_with(s, |p: &mut Parser| {
+        p.literal("position=")?;
+        let position = pair(p)?;
+        p.literal(" velocity=")?;
+        let velocity = pair(p)?;
+        Ok(Point { position, velocity })
+    })
 }
 
-fn parse_line(s: &str) -> Point {
-    let fields: Vec<_> = s.split(delim).map(|s| s.trim()).collect();
-    Point {
-        position: (fields[1].parse().unwrap(), fields[2].parse().unwrap()),
-        velocity: (fields[4].parse().unwrap(), fields[5].parse().unwrap()),
+fn parse_input(s: &str) -> Result<Graph, ParseError> {
+    let points = parse_lines(s, parse_line)?;
+    if points.is_empty() {
+        return Err(ParseError::new("a point", "").at_line(1));
     }
+    Ok(Graph(points))
 }
 
-fn parse_input(s: &str) -> Graph {
-    Graph(s.lines().map(|l| parse_line(l)).collect())
+/// Returns the error for points which never spread apart
+fn no_message() -> ParseError {
+    ParseError::new("points with different velocities", "")
 }
 
-fn message(graph: &mut Graph) -> (String, u32) {
+/// Moves the points until they are closest together, and returns the
+/// message they spell and the time it takes. Returns None if the points all
+/// move together, as they never spread apart again.
+fn message(graph: &mut Graph) -> Option<(String, u32)> {
+    let velocity = graph.0[0].velocity;
+    if graph.0.iter().all(|point| point.velocity == velocity) {
+        return None;
+    }
     let mut width = graph.width();
     let mut height = graph.height();
     let mut time = 0;
@@ -116,27 +124,91 @@ fn message(graph: &mut Graph) -> (String, u32) {
         width = width_new;
         height = height_new;
     }
-    (graph.to_string(), time)
+    Some((graph.to_string(), time))
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        // fall back to the message itself if it is not in the usual font,
+        // like the example
+        let (msg, _) = message(&mut parse_input(input)?).ok_or_else(no_message)?;
+        Ok(ocr_str(&msg).map_or_else(|_| Answer::lines(&msg), Answer::from))
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        let (_, time) = message(&mut parse_input(input)?).ok_or_else(no_message)?;
+        Ok(time.into())
+    }
+
+    fn draw(&self, input: &str) -> Result<Option<Drawing>, SolveError> {
+        let mut graph = parse_input(input)?;
+        message(&mut graph).ok_or_else(no_message)?;
+        let (grid, _) = graph.to_sparse_grid().to_grid();
+        Ok(Some(Drawing::Image(grid.map(|on| {
+            if *on {
+                Color::WHITE
+            } else {
+                Color::BLACK
+            }
+        }))))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::{EXAMPLE, INPUT};
+
+    #[test]
+    fn test_parse_line() {
+        let point = parse_line("position=< 9,  1> velocity=< 0,  2>").unwrap();
+        assert_eq!((point.position, point.velocity), ((9, 1), (0, 2)));
+        let point = parse_line("position=<-3, 11> velocity=< 1, -2>").unwrap();
+        assert_eq!((point.position, point.velocity), ((-3, 11), (1, -2)));
+
+        let tests = &[
+            (
+                "position=< 9,  1> velocity=< 0,  z>",
+                ParseError::new("a number", "z").at_column(34),
+            ),
+            (
+                "position=< 9,  1>",
+                ParseError::new("\" velocity=\"", "").at_column(18),
+            ),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(parse_line(input).unwrap_err(), *expected);
+        }
+    }
 
     #[test]
     fn test_message() {
-        let input = include_str!("10.example.input");
-        let solution = include_str!("10.example.solution");
-        let mut graph = parse_input(&input);
-        let (msg, time) = message(&mut graph);
+        let input = crate::input(10, EXAMPLE);
+        let solution = crate::expected(10, EXAMPLE);
+        let mut graph = parse_input(input).unwrap();
+        let (msg, time) = message(&mut graph).unwrap();
         assert_eq!(msg, solution);
         assert_eq!(time, 3);
 
-        let input = include_str!("10.input");
-        let solution = include_str!("10.solution");
-        let mut graph = parse_input(&input);
-        let (msg, time) = message(&mut graph);
+        let input = crate::input(10, INPUT);
+        let solution = crate::expected(10, INPUT);
+        let mut graph = parse_input(input).unwrap();
+        let (msg, time) = message(&mut graph).unwrap();
         assert_eq!(msg, solution);
+        assert_eq!(ocr_str(&msg), Ok("EJZEAAPE".to_string()));
         assert_eq!(time, 10054);
+
+        let input = "position=< 1,  1> velocity=< 2, -1>
+position=< 3,  5> velocity=< 2, -1>";
+        let mut graph = parse_input(input).unwrap();
+        assert_eq!(message(&mut graph), None);
+        let expected = ParseError::new("a point", "").at_line(1);
+        assert_eq!(parse_input("").unwrap_err(), expected);
     }
 }
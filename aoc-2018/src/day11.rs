@@ -1,62 +1,68 @@
-use std::collections::HashMap;
-use std::fmt;
+use aoc_common::{parse_number, Answer, Grid, ParseError, Point, Solution, SolveError};
 
 const GRID_SIZE: usize = 300;
 
-#[derive(Debug)]
-struct Grid(Vec<Vec<i32>>);
-
-impl fmt::Display for Grid {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for column in self.0.iter().skip(1).take(100) {
-            for row in column.iter().skip(1).take(100) {
-                write!(f, "{:2} ", row)?
-            }
-            writeln!(f, "")?
-        }
-        Ok(())
+/// Returns the power level of every fuel cell. Fuel cell coordinates start
+/// at 1, so the cell at (x, y) is stored at `Point(x - 1, y - 1)`.
+fn power_grid(serial: i32) -> Grid<i32> {
+    let mut grid = Grid::new(GRID_SIZE, GRID_SIZE, 0);
+    for point in grid.points().collect::<Vec<_>>() {
+        grid[point] = power(point.0 + 1, point.1 + 1, serial);
     }
+    grid
 }
 
-impl Grid {
-    fn new(serial: i32) -> Grid {
-        let mut grid = vec![];
-        grid.push(vec![]);
-        for x in 1..=GRID_SIZE {
-            let mut column = vec![0];
-            for y in 1..=GRID_SIZE {
-                column.push(power(x, y, serial));
-            }
-            grid.push(column)
+/// A summed-area table, which gives the total power of any square of fuel
+/// cells in constant time. The entry at (x, y) is the sum of every cell
+/// above and to the left of cell (x, y), so it has an extra row and column.
+struct SummedArea(Grid<i32>);
+
+impl SummedArea {
+    fn new(grid: &Grid<i32>) -> Self {
+        let mut sums = Grid::new(grid.width() + 1, grid.height() + 1, 0);
+        for (Point(x, y), power) in grid.iter() {
+            sums[Point(x + 1, y + 1)] =
+                power + sums[Point(x, y + 1)] + sums[Point(x + 1, y)] - sums[Point(x, y)];
         }
-        Grid(grid)
+        SummedArea(sums)
     }
-}
 
-fn max_power(grid: &Grid) -> (usize, usize) {
-    let mut powers = HashMap::new();
-    for (x, columns) in grid.0.windows(3).enumerate() {
-        for column in columns {
-            for (y, rows) in column.windows(3).enumerate() {
-                *powers.entry((x, y)).or_insert(0) += rows.iter().sum::<i32>();
-            }
+    /// Returns the total power of the square with its top-left cell at
+    /// `origin`, or None if the square does not fit in the grid
+    fn square(&self, origin: Point<usize>, size: usize) -> Option<i32> {
+        let Point(x, y) = origin;
+        let (x2, y2) = (x + size, y + size);
+        if x2 >= self.0.width() || y2 >= self.0.height() {
+            return None;
         }
+        let sums = &self.0;
+        Some(sums[Point(x2, y2)] - sums[Point(x, y2)] - sums[Point(x2, y)] + sums[Point(x, y)])
     }
-    powers.into_iter().max_by_key(|&(_, v)| v).unwrap().0
 }
 
-fn max_power_2(grid: &Grid) -> (usize, usize, usize) {
-    let mut powers = HashMap::new();
-    for size in 1..=grid.0.len() {
-        for (x, columns) in grid.0.windows(size).enumerate() {
-            for column in columns {
-                for (y, rows) in column.windows(size).enumerate() {
-                    *powers.entry((x, y, size)).or_insert(0) += rows.iter().sum::<i32>();
-                }
-            }
-        }
-    }
-    powers.into_iter().max_by_key(|&(_, v)| v).unwrap().0
+/// Returns the coordinates of the top-left fuel cell of the square with the
+/// most power, along with its power
+fn max_square(grid: &Grid<i32>, sums: &SummedArea, size: usize) -> Option<((usize, usize), i32)> {
+    grid.points()
+        .filter_map(|origin| {
+            let Point(x, y) = origin;
+            sums.square(origin, size)
+                .map(|power| ((x + 1, y + 1), power))
+        })
+        .max_by_key(|&(_, power)| power)
+}
+
+fn max_power(grid: &Grid<i32>) -> (usize, usize) {
+    max_square(grid, &SummedArea::new(grid), 3).unwrap().0
+}
+
+fn max_power_2(grid: &Grid<i32>) -> (usize, usize, usize) {
+    let sums = SummedArea::new(grid);
+    let ((x, y), _, size) = (1..=grid.width())
+        .filter_map(|size| max_square(grid, &sums, size).map(|(point, power)| (point, power, size)))
+        .max_by_key(|&(_, power, _)| power)
+        .unwrap();
+    (x, y, size)
 }
 
 fn power(x: usize, y: usize, serial: i32) -> i32 {
@@ -71,6 +77,28 @@ fn hundreds_digit(n: i32) -> i32 {
     n / 100 % 10
 }
 
+fn parse_input(s: &str) -> Result<i32, ParseError> {
+    parse_number(s)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        let (x, y) = max_power(&power_grid(parse_input(input)?));
+        Ok(format!("{},{}", x, y).into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        let (x, y, size) = max_power_2(&power_grid(parse_input(input)?));
+        Ok(format!("{},{},{}", x, y, size).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -90,24 +118,44 @@ mod tests {
         assert_eq!(power(101, 153, 71), 4);
     }
 
+    #[test]
+    fn test_summed_area() {
+        let grid = power_grid(18);
+        let sums = SummedArea::new(&grid);
+        let origin = Point(32, 44);
+        let expected: i32 = grid
+            .view(origin, 3, 3)
+            .unwrap()
+            .iter()
+            .map(|(_, power)| power)
+            .sum();
+        assert_eq!(sums.square(origin, 3), Some(expected));
+        assert_eq!(sums.square(origin, 3), Some(29));
+        assert_eq!(
+            sums.square(Point(0, 0), GRID_SIZE),
+            Some(grid.iter().map(|(_, power)| power).sum())
+        );
+        assert_eq!(sums.square(Point(1, 0), GRID_SIZE), None);
+    }
+
     #[test]
     fn test_max_power() {
-        let grid = Grid::new(18);
+        let grid = power_grid(18);
         assert_eq!(max_power(&grid), (33, 45));
 
-        let grid = Grid::new(8772);
+        let grid = power_grid(8772);
         assert_eq!(max_power(&grid), (235, 31));
     }
 
     #[test]
     fn test_max_power_2() {
-        let grid = Grid::new(18);
+        let grid = power_grid(18);
         assert_eq!(max_power_2(&grid), (90, 269, 16));
 
-        let grid = Grid::new(42);
+        let grid = power_grid(42);
         assert_eq!(max_power_2(&grid), (232, 251, 12));
 
-        let grid = Grid::new(8772);
+        let grid = power_grid(8772);
         assert_eq!(max_power_2(&grid), (241, 65, 10));
     }
 }
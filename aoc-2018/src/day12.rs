@@ -1,11 +1,14 @@
+use aoc_common::{
+    find_cycle, parse_with, Answer, ParseError, Parser, Point, Solution, SolveError, SparseGrid,
+};
 use std::collections::HashMap;
-use std::convert;
+use std::convert::{self, TryFrom};
 use std::fmt;
+use std::iter;
 use std::str::FromStr;
 
 const RULE_LEN: usize = 5;
-// const GENERATIONS: usize = 20;
-const GENERATIONS: usize = 50_000_000_000;
+const GENERATIONS: usize = 20;
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 enum Pot {
@@ -13,12 +16,14 @@ enum Pot {
     Plant,
 }
 
-impl convert::From<char> for Pot {
-    fn from(c: char) -> Self {
+impl TryFrom<char> for Pot {
+    type Error = ParseError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
         match c {
-            '.' => Pot::NoPlant,
-            '#' => Pot::Plant,
-            _ => panic!("invalid pot character"),
+            '.' => Ok(Pot::NoPlant),
+            '#' => Ok(Pot::Plant),
+            _ => Err(ParseError::new("a pot", &c.to_string())),
         }
     }
 }
@@ -32,17 +37,17 @@ impl convert::From<Pot> for char {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Hash)]
-struct Pots {
-    pots: Vec<Pot>,
-    first_idx: isize,
-}
+/// The pots in a row, with the pots outside the bounds of the sparse grid
+/// empty. Pot numbers are the X coordinates of a single row of the grid.
+#[derive(Clone, Debug)]
+struct Pots(SparseGrid<Pot>);
 
 impl fmt::Display for Pots {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for (i, pot) in self.pots.iter().cloned().enumerate() {
-            let c: char = pot.into();
-            if i as isize + self.first_idx == 0 {
+        let (first, last) = self.range();
+        for i in first.min(0)..=last.max(0) {
+            let c: char = self.get(i).into();
+            if i == 0 {
                 write!(f, "[{}]", c)?
             } else {
                 write!(f, "{}", c)?
@@ -53,103 +58,242 @@ impl fmt::Display for Pots {
 }
 
 impl FromStr for Pots {
-    type Err = std::string::ParseError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let pots: Vec<_> = s.chars().map(|c| Pot::from(c)).collect();
-        Ok(Pots {
-            pots: pots,
-            first_idx: 0,
-        })
+        Ok(Pots::from_pots(&parse_pots(s)?))
+    }
+}
+
+/// Parses a single pot
+fn pot(p: &mut Parser) -> Result<Pot, ParseError> {
+    p.satisfy("a pot", |c| Pot::try_from(c).ok())
+}
+
+/// Parses pots up to the end of the line
+fn pots(p: &mut Parser) -> Result<Vec<Pot>, ParseError> {
+    let mut pots = Vec::new();
+    while !p.is_empty() {
+        pots.push(pot(p)?);
     }
+    Ok(pots)
+}
+
+fn parse_pots(s: &str) -> Result<Vec<Pot>, ParseError> {
+    parse_with(s, pots)
 }
 
 impl Pots {
+    /// Creates pots numbered from zero
+    fn from_pots(pots: &[Pot]) -> Self {
+        let mut grid = SparseGrid::new(Pot::NoPlant);
+        for (i, pot) in pots.iter().enumerate() {
+            if *pot == Pot::Plant {
+                grid.insert(Point(i as i64, 0), Pot::Plant);
+            }
+        }
+        Pots(grid)
+    }
+
+    fn get(&self, i: i64) -> Pot {
+        self.0.get(Point(i, 0)).clone()
+    }
+
+    /// Returns the first and last pot numbers with plants
+    fn range(&self) -> (i64, i64) {
+        self.0
+            .bounds()
+            .map_or((0, -1), |(Point(first, _), Point(last, _))| (first, last))
+    }
+
+    /// Returns the number of pots with plants
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns the pot numbers with plants relative to the first, which are
+    /// the same for a pattern wherever it is in the row
+    fn shape(&self) -> Vec<i64> {
+        let (first, _) = self.range();
+        let mut shape: Vec<_> = self.0.iter().map(|(Point(i, _), _)| i - first).collect();
+        shape.sort_unstable();
+        shape
+    }
+
     fn sum_of_numbers(&self) -> isize {
-        self.pots
-            .iter()
-            .cloned()
-            .enumerate()
-            .filter(|(_, pot)| pot == &Pot::Plant)
-            .map(|(i, _)| i as isize + self.first_idx)
-            .sum()
+        self.0.iter().map(|(Point(i, _), _)| i as isize).sum()
     }
-}
 
-fn parse_initial_state(s: &str) -> Pots {
-    let fields: Vec<_> = s.split_whitespace().collect();
-    let mut pots: Pots = fields[2].parse().unwrap();
-    // add buffer around pots
-    for _ in 0..(RULE_LEN - 1) * GENERATIONS {
-        pots.first_idx -= 1;
-        pots.pots.insert(0, Pot::NoPlant);
-        pots.pots.push(Pot::NoPlant)
+    /// Returns the pots in the next generation. Only pots within two of a
+    /// plant can have a plant next generation, assuming an empty pattern
+    /// never grows a plant.
+    fn next(&self, rules: &Rules) -> Self {
+        let (first, last) = self.range();
+        let mut pots = SparseGrid::new(Pot::NoPlant);
+        let offset = (RULE_LEN / 2) as i64;
+        for i in first - offset..=last + offset {
+            let pattern: Vec<Pot> = (i - offset..=i + offset).map(|i| self.get(i)).collect();
+            if rules.get(&pattern) == Some(&Pot::Plant) {
+                pots.insert(Point(i, 0), Pot::Plant);
+            }
+        }
+        Pots(pots)
     }
-    pots
 }
 
-type Rules = HashMap<Pots, Pot>;
+/// Parses a line like "initial state: #..#"
+fn parse_initial_state(s: &str) -> Result<Pots, ParseError> {
+    let pots = parse_with(s, |p: &mut Parser| {
+        p.literal("initial state: ")?;
+        pots(p)
+    })?;
+    Ok(Pots::from_pots(&pots))
+}
+
+type Rules = HashMap<Vec<Pot>, Pot>;
 
-fn parse_rules(lines: &[String]) -> Rules {
-    lines
-        .into_iter()
-        .map(|l| {
-            let fields: Vec<_> = l.splitn(2, "=>").collect();
-            let pots: Pots = fields[0].parse().unwrap();
-            let pot = Pot::from(fields[1].chars().next().unwrap());
-            (pots, pot)
-        })
-        .collect()
+/// Parses a rule like "..#.. => #"
+fn parse_rule(s: &str) -> Result<(Vec<Pot>, Pot), ParseError> {
+    parse_with(s, |p: &mut Parser| {
+        let pattern = (0..RULE_LEN).map(|_| pot(p)).collect::<Result<_, _>>()?;
+        p.literal(" => ")?;
+        Ok((pattern, pot(p)?))
+    })
 }
 
-fn parse_input(s: &str) -> (Pots, Rules) {
+fn parse_input(s: &str) -> Result<(Pots, Rules), ParseError> {
     let mut lines = s.lines();
-    let pots = parse_initial_state(lines.next().unwrap());
-    let lines: Vec<_> = lines
-        .skip(1)
-        .map(|l| l.split_whitespace().collect::<String>())
-        .collect();
-    let rules = parse_rules(&lines);
-    (pots, rules)
+    let pots = parse_initial_state(lines.next().unwrap_or("")).map_err(|err| err.at_line(1))?;
+    match lines.next() {
+        Some(line) if !line.is_empty() => {
+            return Err(ParseError::new("an empty line", line).at_line(2));
+        }
+        _ => {}
+    }
+    let rules = lines
+        .enumerate()
+        .map(|(i, line)| parse_rule(line).map_err(|err| err.at_line(i + 3)))
+        .collect::<Result<_, _>>()?;
+    Ok((pots, rules))
 }
 
 fn sum_of_pots(pots: &mut Pots, rules: &Rules, generations: usize) -> isize {
-    if generations == 0 {
-        return pots.sum_of_numbers();
-    }
-
-    let first_idx = pots.first_idx;
-    let pots: Vec<Pot> = pots
-        .pots
-        .windows(RULE_LEN)
-        .map(|pots| {
-            let pots = Pots {
-                pots: pots.to_vec(),
-                first_idx: 0,
-            };
-            rules.get(&pots).unwrap_or(&Pot::NoPlant)
-        })
-        .cloned()
-        .collect();
-    let mut pots = Pots {
-        pots,
-        first_idx: first_idx + 2,
-    };
-    sum_of_pots(&mut pots, rules, generations - 1)
+    for _ in 0..generations {
+        *pots = pots.next(rules);
+    }
+    pots.sum_of_numbers()
+}
+
+/// Returns the sum of the pot numbers with plants after `generations`. The
+/// plants settle into a pattern which repeats as it moves along the row, so
+/// the sum is extrapolated from the first repeat.
+fn sum_of_pots_after(pots: &Pots, rules: &Rules, generations: usize) -> isize {
+    let states = || iter::successors(Some(pots.clone()), |pots| Some(pots.next(rules)));
+    let cycle = find_cycle(states().map(|pots| pots.shape())).unwrap();
+    let n = cycle.reduce(generations);
+    let periods = ((generations - n) / cycle.period) as isize;
+
+    let mut later = states().skip(n);
+    let start = later.next().unwrap();
+    let end = later.nth(cycle.period - 1).unwrap();
+    let shift = (end.range().0 - start.range().0) as isize;
+    start.sum_of_numbers() + periods * shift * start.len() as isize
+}
+
+/// Part 2 asks for the sum after this many generations
+const GENERATIONS_2: usize = 50_000_000_000;
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        let (mut pots, rules) = parse_input(input)?;
+        Ok(sum_of_pots(&mut pots, &rules, GENERATIONS).into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        let (pots, rules) = parse_input(input)?;
+        Ok(sum_of_pots_after(&pots, &rules, GENERATIONS_2).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::{EXAMPLE, INPUT};
+
+    #[test]
+    fn test_parse_input() {
+        let (pots, rules) = parse_input("initial state: #..#\n\n..#.# => #\n").unwrap();
+        assert_eq!(pots.to_string(), "[#]..#");
+        let pattern = vec![
+            Pot::NoPlant,
+            Pot::NoPlant,
+            Pot::Plant,
+            Pot::NoPlant,
+            Pot::Plant,
+        ];
+        assert_eq!(rules.get(&pattern), Some(&Pot::Plant));
+
+        let tests = &[
+            (
+                "initial: #..#\n",
+                ParseError::new("\"initial state: \"", "initial")
+                    .at_column(1)
+                    .at_line(1),
+            ),
+            (
+                "initial state: #..#\n\n..#.# => #\n..x.# => #\n",
+                ParseError::new("a pot", "x").at_line(4).at_column(3),
+            ),
+            (
+                "initial state: #..#\n\n..#.# => #\n..## => #\n",
+                ParseError::new("a pot", " =").at_column(5).at_line(4),
+            ),
+            (
+                "initial state: #..#\n..#.# => #\n",
+                ParseError::new("an empty line", "..#.# => #").at_line(2),
+            ),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(parse_input(input).unwrap_err(), *expected);
+        }
+    }
 
     #[test]
     fn test() {
-        // let input = include_str!("12.example.input");
-        // let (mut pots, rules) = parse_input(&input);
-        // assert_eq!(sum_of_pots(&mut pots, &rules, GENERATIONS), 325);
+        let input = crate::input(12, EXAMPLE);
+        let (mut pots, rules) = parse_input(input).unwrap();
+        assert_eq!(sum_of_pots(&mut pots, &rules, GENERATIONS), 325);
 
-        let input = include_str!("12.input");
-        let (mut pots, rules) = parse_input(&input);
+        let input = crate::input(12, INPUT);
+        let (mut pots, rules) = parse_input(input).unwrap();
         assert_eq!(sum_of_pots(&mut pots, &rules, GENERATIONS), 3241);
     }
+
+    #[test]
+    fn test_sum_of_pots_after() {
+        let input = crate::input(12, EXAMPLE);
+        let (pots, rules) = parse_input(input).unwrap();
+        assert_eq!(sum_of_pots_after(&pots, &rules, GENERATIONS), 325);
+
+        let input = crate::input(12, INPUT);
+        let (pots, rules) = parse_input(input).unwrap();
+        let mut simulated = pots.clone();
+        for generations in 0..200 {
+            let expected = simulated.sum_of_numbers();
+            if generations % 10 == 0 {
+                assert_eq!(sum_of_pots_after(&pots, &rules, generations), expected);
+            }
+            simulated = simulated.next(&rules);
+        }
+        assert_eq!(
+            sum_of_pots_after(&pots, &rules, GENERATIONS_2),
+            2749999999911
+        );
+    }
 }
@@ -1,3 +1,4 @@
+use aoc_common::{Answer, Solution, SolveError};
 use std::collections::HashMap;
 
 fn appears_n(s: &str, n: i32) -> bool {
@@ -48,14 +49,32 @@ fn common_chars(s1: &str, s2: &str) -> String {
         .collect()
 }
 
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        let ids: Vec<_> = input.lines().collect();
+        Ok(checksum(&ids).into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        let ids: Vec<_> = input.lines().collect();
+        Ok(close_ids(&ids)
+            .first()
+            .map(|(id1, id2)| common_chars(id1, id2))
+            .into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::INPUT;
 
     #[test]
     fn test_appears_n() {
-        assert_eq!(appears_n("aabcdefg", 2), true);
-        assert_eq!(appears_n("aabcdefg", 3), false);
+        assert!(appears_n("aabcdefg", 2));
+        assert!(!appears_n("aabcdefg", 3));
     }
 
     #[test]
@@ -65,7 +84,7 @@ mod tests {
         ];
         assert_eq!(checksum(&ids), 12);
 
-        let input = include_str!("2.input");
+        let input = crate::input(2, INPUT);
         let ids: Vec<_> = input.lines().collect();
         assert_eq!(checksum(&ids), 8820);
     }
@@ -83,12 +102,12 @@ mod tests {
         ];
         let close = close_ids(&ids);
         let (id1, id2) = close.first().unwrap();
-        assert_eq!(common_chars(&id1, &id2), "fgij");
+        assert_eq!(common_chars(id1, id2), "fgij");
 
-        let input = include_str!("2.input");
+        let input = crate::input(2, INPUT);
         let ids: Vec<_> = input.lines().collect();
         let close = close_ids(&ids);
         let (id1, id2) = close.first().unwrap();
-        assert_eq!(common_chars(&id1, &id2), "bpacnmglhizqygfsjixtkwudr");
+        assert_eq!(common_chars(id1, id2), "bpacnmglhizqygfsjixtkwudr");
     }
 }
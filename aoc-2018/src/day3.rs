@@ -1,3 +1,5 @@
+use aoc_common::{parse_lines, parse_with, Answer, ParseError, Parser, Solution, SolveError};
+
 #[derive(Debug)]
 struct Claim {
     id: u32,
@@ -7,30 +9,31 @@ struct Claim {
     height: usize,
 }
 
-fn parse_claim(s: &str) -> Claim {
-    let fields: Vec<_> = s.split_whitespace().collect();
-    let id = fields[0].trim_start_matches('#').parse::<u32>().unwrap();
-    let loc: Vec<_> = fields[2]
-        .trim_end_matches(':')
-        .split(',')
-        .map(|s| s.parse::<usize>().unwrap())
-        .collect();
-    let origin_x = loc[0];
-    let origin_y = loc[1];
-    let dims: Vec<_> = fields[3]
-        .split('x')
-        .map(|s| s.parse::<usize>().unwrap())
-        .collect();
-    let width = dims[0];
-    let height = dims[1];
-
-    Claim {
-        id,
-        origin_x,
-        origin_y,
-        width,
-        height,
-    }
+/// Parses a claim like "#1 @ 1,3: 4x4"
+fn parse_claim(s: &str) -> Result<Claim, ParseError> {
+    parse_with(s, |p: &mut Parser| {
+        p.literal("#")?;
+        let id = p.unsigned()?;
+        p.literal(" @ ")?;
+        let origin_x = p.unsigned()?;
+        p.literal(",")?;
+        let origin_y = p.unsigned()?;
+        p.literal(": ")?;
+        let width = p.unsigned()?;
+        p.literal("x")?;
+        let height = p.unsigned()?;
+        Ok(Claim {
+            id,
+            origin_x,
+            origin_y,
+            width,
+            height,
+        })
+    })
+}
+
+fn parse_input(s: &str) -> Result<Vec<Claim>, ParseError> {
+    parse_lines(s, parse_claim)
 }
 
 type Sheet = Vec<u32>;
@@ -67,7 +70,7 @@ fn overlaps(size: usize, claims: &[Claim]) -> u32 {
 }
 
 fn no_overlaps(size: usize, claims: &[Claim]) -> Vec<u32> {
-    let sheet = apply_claims(size, &claims);
+    let sheet = apply_claims(size, claims);
 
     let mut ok_claims = vec![];
     for c in claims {
@@ -87,22 +90,69 @@ fn no_overlaps(size: usize, claims: &[Claim]) -> Vec<u32> {
     ok_claims
 }
 
+/// Returns the size of the smallest square sheet which fits every claim
+fn sheet_size(claims: &[Claim]) -> usize {
+    claims
+        .iter()
+        .map(|c| std::cmp::max(c.origin_x + c.width, c.origin_y + c.height))
+        .max()
+        .unwrap_or(0)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        let claims = parse_input(input)?;
+        Ok(overlaps(sheet_size(&claims), &claims).into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        let claims = parse_input(input)?;
+        Ok(no_overlaps(sheet_size(&claims), &claims)
+            .first()
+            .cloned()
+            .into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::INPUT;
+
+    #[test]
+    fn test_parse_claim() {
+        let claim = parse_claim("#123 @ 3,2: 5x4").unwrap();
+        assert_eq!(claim.id, 123);
+        assert_eq!((claim.origin_x, claim.origin_y), (3, 2));
+        assert_eq!((claim.width, claim.height), (5, 4));
+
+        let input = "#1 @ 1,3: 4x4\n#2 @ 3,1 4x4\n";
+        let expected = ParseError::new("\": \"", " 4x4").at_column(9).at_line(2);
+        assert_eq!(parse_input(input).unwrap_err(), expected);
+
+        let input = "#1 @ 1,3: 4x4\n#2 @ 3,1: 4xfour\n";
+        let expected = ParseError::new("a number", "four").at_column(13).at_line(2);
+        assert_eq!(parse_input(input).unwrap_err(), expected);
+    }
 
     #[test]
     fn test_overlaps() {
-        let input = include_str!("3.input");
-        let claims: Vec<_> = input.lines().map(|s| parse_claim(s)).collect();
+        let input = crate::input(3, INPUT);
+        let claims = parse_input(input).unwrap();
 
         assert_eq!(overlaps(5000, &claims), 115304);
     }
 
     #[test]
     fn test_no_overlaps() {
-        let input = include_str!("3.input");
-        let claims: Vec<_> = input.lines().map(|s| parse_claim(s)).collect();
+        let input = crate::input(3, INPUT);
+        let claims = parse_input(input).unwrap();
 
         let ok_claims = no_overlaps(5000, &claims);
         assert_eq!(ok_claims.len(), 1);
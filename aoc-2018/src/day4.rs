@@ -1,3 +1,4 @@
+use aoc_common::{parse_lines, parse_with, Answer, ParseError, Parser, Solution, SolveError};
 use std::cmp::Ordering;
 use std::collections::HashMap;
 
@@ -51,44 +52,52 @@ impl Nap {
     }
 }
 
-fn parse_line(s: &str) -> Entry {
-    let parts: Vec<_> = s.splitn(3, ' ').collect();
-
-    let date: Vec<_> = parts[0]
-        .trim_start_matches('[')
-        .split('-')
-        .map(|s| s.parse::<u32>().unwrap())
-        .collect();
-
-    let time: Vec<_> = parts[1]
-        .trim_end_matches(']')
-        .splitn(2, ':')
-        .map(|s| s.parse::<u32>().unwrap())
-        .collect();
-
-    let time = (date[0], date[1], date[2], time[0], time[1]);
+/// Parses an entry like "[1518-11-01 00:00] Guard #10 begins shift"
+fn parse_line(s: &str) -> Result<Entry, ParseError> {
+    parse_with(s, |p: &mut Parser| {
+        p.literal("[")?;
+        let year = p.unsigned()?;
+        p.literal("-")?;
+        let month = p.unsigned()?;
+        p.literal("-")?;
+        let day = p.unsigned()?;
+        p.literal(" ")?;
+        let hour = p.unsigned()?;
+        p.literal(":")?;
+        let minute = p.unsigned()?;
+        p.literal("] ")?;
+        let time = (year, month, day, hour, minute);
+
+        let event = if p.try_literal("falls asleep") {
+            Event::FallsAsleep
+        } else if p.try_literal("wakes up") {
+            Event::WakesUp
+        } else {
+            p.literal("Guard ")?;
+            p.literal("#")?;
+            let id = p.unsigned()?;
+            p.literal(" begins shift")?;
+            Event::BeginsShift(id)
+        };
 
-    let message = parts[2];
+        Ok(Entry { time, event })
+    })
+}
 
-    let event = match message {
-        "falls asleep" => Event::FallsAsleep,
-        "wakes up" => Event::WakesUp,
-        s => {
-            let id = s
-                .split_whitespace()
-                .find(|s| s.starts_with('#'))
-                .unwrap()
-                .trim_start_matches('#')
-                .parse::<u32>()
-                .unwrap();
-            Event::BeginsShift(id)
-        }
-    };
+fn parse_input(s: &str) -> Result<Vec<Entry>, ParseError> {
+    let entries = parse_lines(s, parse_line)?;
+    if entries.is_empty() {
+        return Err(ParseError::new("a guard record", "").at_line(1));
+    }
+    Ok(entries)
+}
 
-    Entry { time, event }
+/// Returns the error for records where no guard takes a nap
+fn no_naps() -> ParseError {
+    ParseError::new("a guard who falls asleep", "")
 }
 
-fn max_time(naps: &[&Nap]) -> u32 {
+fn max_time(naps: &[&Nap]) -> Option<u32> {
     let mut occurences = HashMap::new();
     for nap in naps {
         for i in nap.start..nap.end {
@@ -96,10 +105,10 @@ fn max_time(naps: &[&Nap]) -> u32 {
         }
     }
 
-    occurences.into_iter().max_by_key(|&(_, v)| v).unwrap().0
+    Some(occurences.into_iter().max_by_key(|&(_, v)| v)?.0)
 }
 
-fn max_time_2(naps: &[Nap]) -> (u32, u32) {
+fn max_time_2(naps: &[Nap]) -> Option<(u32, u32)> {
     let mut occurences = HashMap::new();
     for nap in naps {
         for i in nap.start..nap.end {
@@ -107,37 +116,49 @@ fn max_time_2(naps: &[Nap]) -> (u32, u32) {
         }
     }
 
-    occurences.into_iter().max_by_key(|&(_, v)| v).unwrap().0
+    Some(occurences.into_iter().max_by_key(|&(_, v)| v)?.0)
 }
 
-fn lines_to_naps(lines: &[&str]) -> Vec<Nap> {
+/// Collects the naps in the records. Fails if a guard wakes up without
+/// having fallen asleep on their shift.
+fn entries_to_naps(entries: &[Entry]) -> Result<Vec<Nap>, ParseError> {
     let mut naps: Vec<Nap> = vec![];
 
-    let mut nap = Nap {
-        id: 0,
-        start: 0,
-        end: 0,
-    };
+    let mut id = None;
+    let mut start = None;
 
-    let mut entries: Vec<_> = lines.iter().map(|l| parse_line(&l)).collect();
+    let mut entries: Vec<_> = entries.iter().collect();
     entries.sort();
 
-    for entry in &entries {
+    for entry in entries {
         match entry.event {
-            Event::BeginsShift(id) => nap.id = id,
-            Event::FallsAsleep => nap.start = entry.minute(),
+            Event::BeginsShift(guard) => {
+                id = Some(guard);
+                start = None;
+            }
+            Event::FallsAsleep => start = Some(entry.minute()),
             Event::WakesUp => {
-                nap.end = entry.minute();
-                naps.push(nap.clone())
+                let end = entry.minute();
+                match (id, start.take()) {
+                    (Some(id), Some(start)) if start <= end => naps.push(Nap { id, start, end }),
+                    _ => {
+                        let (_, month, day, hour, minute) = entry.time;
+                        let text = format!(
+                            "wakes up at {:02}-{:02} {:02}:{:02}",
+                            month, day, hour, minute
+                        );
+                        return Err(ParseError::new("a guard who fell asleep", &text));
+                    }
+                }
             }
         }
     }
 
-    naps
+    Ok(naps)
 }
 
-fn id_times_minute(lines: &[&str]) -> u32 {
-    let naps = lines_to_naps(lines);
+fn id_times_minute(entries: &[Entry]) -> Result<u32, ParseError> {
+    let naps = entries_to_naps(entries)?;
 
     let mut durations = HashMap::new();
 
@@ -148,44 +169,113 @@ fn id_times_minute(lines: &[&str]) -> u32 {
     let id_max = durations
         .iter()
         .max_by(|(_, v1), (_, v2)| v1.cmp(v2))
-        .unwrap()
+        .ok_or_else(no_naps)?
         .0;
     let naps_for: Vec<_> = naps.iter().filter(|n| &n.id == id_max).collect();
-    let mtime = max_time(&naps_for);
+    let mtime = max_time(&naps_for).ok_or_else(no_naps)?;
 
-    id_max * mtime
+    Ok(id_max * mtime)
 }
 
-fn id_times_minute_2(lines: &[&str]) -> u32 {
-    let naps = lines_to_naps(lines);
-    let (id, minute) = max_time_2(&naps);
+fn id_times_minute_2(entries: &[Entry]) -> Result<u32, ParseError> {
+    let naps = entries_to_naps(entries)?;
+    let (id, minute) = max_time_2(&naps).ok_or_else(no_naps)?;
 
-    id * minute
+    Ok(id * minute)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(id_times_minute(&parse_input(input)?)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(id_times_minute_2(&parse_input(input)?)?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::{EXAMPLE, INPUT};
+
+    #[test]
+    fn test_parse_line() {
+        let tests = &[
+            (
+                "[1518-11-01 00:00] Guard #10 begins shift",
+                Ok(((1518, 11, 1, 0, 0), Event::BeginsShift(10))),
+            ),
+            (
+                "[1518-11-01 00:05] falls asleep",
+                Ok(((1518, 11, 1, 0, 5), Event::FallsAsleep)),
+            ),
+            (
+                "1518-11-01 00:25 wakes up",
+                Err(ParseError::new("\"[\"", "1518").at_column(1)),
+            ),
+            (
+                "[1518-11-01 00:x5] wakes up",
+                Err(ParseError::new("a number", "x5").at_column(16)),
+            ),
+            (
+                "[1518-11-01 00:00] Guard 10 begins shift",
+                Err(ParseError::new("\"#\"", "10").at_column(26)),
+            ),
+            (
+                "[1518-11-01 00:05] falls asleep.",
+                Err(ParseError::new("the end of the line", ".").at_column(32)),
+            ),
+        ];
+        for (input, expected) in tests {
+            let entry = parse_line(input).map(|entry| (entry.time, entry.event));
+            assert_eq!(&entry, expected);
+        }
+    }
 
     #[test]
     fn test_id_times_minute() {
-        let input = include_str!("4.example.input");
-        let lines: Vec<_> = input.lines().collect();
-        assert_eq!(id_times_minute(&lines), 240);
+        let input = crate::input(4, EXAMPLE);
+        let entries = parse_input(input).unwrap();
+        assert_eq!(id_times_minute(&entries), Ok(240));
 
-        let input = include_str!("4.input");
-        let lines: Vec<_> = input.lines().collect();
-        assert_eq!(id_times_minute(&lines), 35623);
+        let input = crate::input(4, INPUT);
+        let entries = parse_input(input).unwrap();
+        assert_eq!(id_times_minute(&entries), Ok(35623));
+    }
+
+    #[test]
+    fn test_invalid_records() {
+        assert_eq!(
+            parse_input(""),
+            Err(ParseError::new("a guard record", "").at_line(1))
+        );
+
+        let entries = parse_input("[1518-11-01 00:00] Guard #10 begins shift").unwrap();
+        assert_eq!(id_times_minute(&entries), Err(no_naps()));
+        assert_eq!(id_times_minute_2(&entries), Err(no_naps()));
+
+        let input = "[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:25] wakes up";
+        let entries = parse_input(input).unwrap();
+        let expected = ParseError::new("a guard who fell asleep", "wakes up at 11-01 00:25");
+        assert_eq!(id_times_minute(&entries), Err(expected));
     }
 
     #[test]
     fn test_foo() {
-        let input = include_str!("4.example.input");
-        let lines: Vec<_> = input.lines().collect();
-        assert_eq!(id_times_minute_2(&lines), 4455);
+        let input = crate::input(4, EXAMPLE);
+        let entries = parse_input(input).unwrap();
+        assert_eq!(id_times_minute_2(&entries), Ok(4455));
 
-        let input = include_str!("4.input");
-        let lines: Vec<_> = input.lines().collect();
-        assert_eq!(id_times_minute_2(&lines), 23037);
+        let input = crate::input(4, INPUT);
+        let entries = parse_input(input).unwrap();
+        assert_eq!(id_times_minute_2(&entries), Ok(23037));
     }
 }
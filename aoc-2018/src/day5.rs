@@ -1,3 +1,4 @@
+use aoc_common::{Answer, ParseError, Solution, SolveError};
 use itertools::Itertools;
 use std::collections::HashSet;
 
@@ -29,9 +30,8 @@ fn react(cs: &[char]) -> Vec<char> {
     }
 
     if !skip {
-        match cs.last() {
-            Some(c) => reacted.push(*c),
-            _ => {}
+        if let Some(c) = cs.last() {
+            reacted.push(*c)
         }
     }
 
@@ -59,9 +59,47 @@ fn shortest_react(cs: &[char]) -> usize {
     rs.iter().map(|r| r.len()).min().unwrap()
 }
 
+/// Parses the polymer, which is a line of at least one unit. Each unit is
+/// a letter.
+fn parse_input(s: &str) -> Result<Vec<char>, ParseError> {
+    let polymer = s
+        .trim_end()
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            if c.is_ascii_alphabetic() {
+                Ok(c)
+            } else {
+                Err(ParseError::new("a unit", &c.to_string()).at_column(i + 1))
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if polymer.is_empty() {
+        return Err(ParseError::new("a unit", "").at_column(1));
+    }
+    Ok(polymer)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(react(&parse_input(input)?).len().into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(shortest_react(&parse_input(input)?).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::INPUT;
 
     #[test]
     fn test_react() {
@@ -83,19 +121,32 @@ mod tests {
         assert_eq!(reacted, "dabCBAcaDA".chars().collect::<Vec<_>>());
         assert_eq!(reacted.len(), 10);
 
-        let s = include_str!("5.input").trim_end();
+        let s = crate::input(5, INPUT).trim_end();
         let cs: Vec<_> = s.chars().collect();
         let reacted = react(&cs);
         assert_eq!(reacted.len(), 10888);
     }
 
+    #[test]
+    fn test_parse_input() {
+        assert_eq!(parse_input("aBc\n"), Ok(vec!['a', 'B', 'c']));
+        assert_eq!(
+            parse_input("aB3c"),
+            Err(ParseError::new("a unit", "3").at_column(3))
+        );
+        assert_eq!(
+            parse_input("\n"),
+            Err(ParseError::new("a unit", "").at_column(1))
+        );
+    }
+
     #[test]
     fn test_longest_react() {
         let s = "dabAcCaCBAcCcaDA";
         let cs: Vec<_> = s.chars().collect();
         assert_eq!(shortest_react(&cs), 4);
 
-        let s = include_str!("5.input").trim_end();
+        let s = crate::input(5, INPUT).trim_end();
         let cs: Vec<_> = s.chars().collect();
         assert_eq!(shortest_react(&cs), 6952);
     }
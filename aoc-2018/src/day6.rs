@@ -1,135 +1,69 @@
-use std::fmt;
-
-use itertools::Itertools;
-
-#[derive(Clone, Debug, PartialEq)]
-struct Point(usize, usize);
-
-struct Grid {
-    rows: usize,
-    columns: usize,
-    points: Vec<Option<()>>,
-}
-
-impl Grid {
-    fn new(rows: usize, columns: usize) -> Self {
-        Grid {
-            rows,
-            columns,
-            points: vec![None; rows * columns],
-        }
-    }
-
-    fn from_points(points: &[Point]) -> Self {
-        let max_x = points.iter().map(|Point(x, _)| x).max().unwrap();
-        let max_y = points.iter().map(|Point(_, y)| y).max().unwrap();
-        let mut grid = Self::new(*max_y + 1, *max_x + 1);
-        for point in points {
-            Self::on(&mut grid, &point);
-        }
-        grid
-    }
-
-    fn on(&mut self, point: &Point) {
-        let Point(x, y) = point;
-        self.points[*y * self.columns + *x] = Some(())
-    }
-
-    fn all_points(&self) -> Box<dyn Iterator<Item = Point>> {
-        let iter = (0..self.columns)
-            .cartesian_product(0..self.rows)
-            .map(|(x, y)| Point(x, y));
-        Box::new(iter)
-    }
-
-    fn border_points(&self) -> Vec<Point> {
-        let points = self.all_points();
-        points
-            .filter(|Point(x, y)| {
-                *x == 0 || *x == (self.columns - 1) || *y == 0 || *y == (self.rows - 1)
-            })
-            .collect()
-    }
-
-    fn occupied_points(&self) -> Vec<Point> {
-        let mut points = vec![];
-        for x in 0..self.columns {
-            for y in 0..self.rows {
-                if self.points[y * self.columns + x].is_some() {
-                    points.push(Point(x, y))
-                }
-            }
-        }
-        points
+use aoc_common::{
+    manhattan_distance, parse_lines, parse_with, Answer, Color, Drawing, Grid, ParseError, Parser,
+    Point, Solution, SolveError,
+};
+
+/// Returns a grid just big enough to hold the points, with the points set
+fn grid_from_points(points: &[Point<usize>]) -> Grid<bool> {
+    let max_x = points.iter().map(|Point(x, _)| x).max().unwrap();
+    let max_y = points.iter().map(|Point(_, y)| y).max().unwrap();
+    let mut grid = Grid::new(*max_x + 1, *max_y + 1, false);
+    for point in points {
+        grid[*point] = true;
     }
+    grid
 }
 
-impl fmt::Debug for Grid {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let grid = self
-            .points
-            .chunks(self.columns)
-            .map(|row| {
-                row.into_iter()
-                    .map(|p| match p {
-                        Some(_) => 'X',
-                        None => '.',
-                    })
-                    .collect::<String>()
-            })
-            .collect::<Vec<_>>()
-            .join("\n");
-        write!(f, "{}", grid)
-    }
+fn occupied_points(grid: &Grid<bool>) -> Vec<Point<usize>> {
+    grid.iter()
+        .filter(|(_, occupied)| **occupied)
+        .map(|(point, _)| point)
+        .collect()
 }
 
-fn closest_point(p: &Point, ps: &[Point]) -> Option<Point> {
+fn closest_point(p: &Point<usize>, ps: &[Point<usize>]) -> Option<Point<usize>> {
     let shortest = ps.iter().map(|p2| manhattan_distance(p, p2)).min().unwrap();
     let closest: Vec<_> = ps
-        .into_iter()
+        .iter()
         .filter(|p2| manhattan_distance(p, p2) == shortest)
         .collect();
     match closest.as_slice() {
-        [p] => Some(p.clone().clone()),
+        [p] => Some(**p),
         _ => None,
     }
 }
 
-fn parse_line(s: &str) -> Point {
-    let s: String = s.chars().filter(|c| !c.is_ascii_whitespace()).collect();
-    let xy: Vec<_> = s.split(',').map(|s| s.parse::<usize>().unwrap()).collect();
-    match xy.as_slice() {
-        [x, y] => Point(*x, *y),
-        _ => panic!("bad input"),
-    }
+/// Parses a coordinate like "1, 6"
+fn parse_line(s: &str) -> Result<Point<usize>, ParseError> {
+    parse_with(s, |p: &mut Parser| {
+        let x = p.unsigned()?;
+        p.literal(", ")?;
+        let y = p.unsigned()?;
+        Ok(Point(x, y))
+    })
 }
 
-fn parse_lines(lines: &[&str]) -> Vec<Point> {
-    lines.iter().map(|s| parse_line(s)).collect()
-}
-
-fn manhattan_distance(p1: &Point, p2: &Point) -> usize {
-    let Point(x1, y1) = p1;
-    let Point(x2, y2) = p2;
-    ((*x2 as i32 - *x1 as i32).abs() + (*y2 as i32 - *y1 as i32).abs()) as usize
+fn parse_input(s: &str) -> Result<Vec<Point<usize>>, ParseError> {
+    let points = parse_lines(s, parse_line)?;
+    if points.is_empty() {
+        return Err(ParseError::new("a coordinate", "").at_line(1));
+    }
+    Ok(points)
 }
 
-fn get_max_area(points: &[Point]) -> usize {
-    let grid = Grid::from_points(&points);
+fn get_max_area(points: &[Point<usize>]) -> usize {
+    let grid = grid_from_points(points);
+    let occupied = occupied_points(&grid);
 
-    let all_points = grid.all_points();
-    let border_points = grid.border_points();
-    let occupied = grid.occupied_points();
-
-    let border_closest: Vec<_> = border_points
-        .iter()
-        .map(|p| closest_point(p, &occupied))
-        .filter_map(|x| x)
+    let border_closest: Vec<_> = grid
+        .points()
+        .filter(|p| grid.is_border(*p))
+        .filter_map(|p| closest_point(&p, &occupied))
         .collect();
 
-    let closest: Vec<_> = all_points
-        .map(|p| closest_point(&p, &occupied))
-        .filter_map(|x| x)
+    let closest: Vec<_> = grid
+        .points()
+        .filter_map(|p| closest_point(&p, &occupied))
         .filter(|p| !border_closest.contains(p))
         .collect();
 
@@ -140,13 +74,11 @@ fn get_max_area(points: &[Point]) -> usize {
         .unwrap()
 }
 
-fn get_max_area2(points: &[Point], less: usize) -> usize {
-    let grid = Grid::from_points(&points);
-
-    let all_points = grid.all_points();
-    let occupied = grid.occupied_points();
+fn get_max_area2(points: &[Point<usize>], less: usize) -> usize {
+    let grid = grid_from_points(points);
+    let occupied = occupied_points(&grid);
 
-    all_points
+    grid.points()
         .filter(|p1| {
             occupied
                 .iter()
@@ -157,9 +89,52 @@ fn get_max_area2(points: &[Point], less: usize) -> usize {
         .count()
 }
 
+/// Draws the area closest to each coordinate in its own color, with the
+/// coordinates in white and the points closest to more than one in black
+fn regions(points: &[Point<usize>]) -> Grid<Color> {
+    let grid = grid_from_points(points);
+    let occupied = occupied_points(&grid);
+    let mut image = Grid::new(grid.width(), grid.height(), Color::BLACK);
+    for point in grid.points() {
+        image[point] = if grid[point] {
+            Color::WHITE
+        } else {
+            closest_point(&point, &occupied)
+                .and_then(|closest| occupied.iter().position(|p| *p == closest))
+                .map_or(Color::BLACK, Color::indexed)
+        };
+    }
+    image
+}
+
+/// Points in the region are less than this total distance from every
+/// coordinate
+const MAX_TOTAL_DISTANCE: usize = 10000;
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(get_max_area(&parse_input(input)?).into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(get_max_area2(&parse_input(input)?, MAX_TOTAL_DISTANCE).into())
+    }
+
+    fn draw(&self, input: &str) -> Result<Option<Drawing>, SolveError> {
+        Ok(Some(Drawing::Image(regions(&parse_input(input)?))))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::{EXAMPLE, INPUT};
 
     #[test]
     fn test_manhattan_distance() {
@@ -169,34 +144,51 @@ mod tests {
         assert_eq!(manhattan_distance(&p2, &p1), 12);
     }
 
+    #[test]
+    fn test_parse_input() {
+        assert_eq!(
+            parse_input("1, 1\n1, 6\n"),
+            Ok(vec![Point(1, 1), Point(1, 6)])
+        );
+        assert_eq!(
+            parse_input("1, 1\n1, 6, 2\n"),
+            Err(ParseError::new("the end of the line", ",")
+                .at_line(2)
+                .at_column(5))
+        );
+        assert_eq!(
+            parse_input("1, 1\n1, -6\n"),
+            Err(ParseError::new("a number", "-").at_line(2).at_column(4))
+        );
+        assert_eq!(
+            parse_input(""),
+            Err(ParseError::new("a coordinate", "").at_line(1))
+        );
+    }
+
     #[test]
     fn test_max_area() {
-        let input = include_str!("6.example.input");
-        let lines: Vec<_> = input.lines().collect();
-        let points = parse_lines(&lines);
+        let input = crate::input(6, EXAMPLE);
+        let points = parse_input(input).unwrap();
         assert_eq!(get_max_area(&points), 17);
 
-        let input = include_str!("6.example2.input");
-        let lines: Vec<_> = input.lines().collect();
-        let points = parse_lines(&lines);
+        let input = crate::input(6, "example2");
+        let points = parse_input(input).unwrap();
         assert_eq!(get_max_area(&points), 9);
 
-        let input = include_str!("6.input");
-        let lines: Vec<_> = input.lines().collect();
-        let points = parse_lines(&lines);
+        let input = crate::input(6, INPUT);
+        let points = parse_input(input).unwrap();
         assert_eq!(get_max_area(&points), 4475);
     }
 
     #[test]
     fn test_max_area2() {
-        let input = include_str!("6.example.input");
-        let lines: Vec<_> = input.lines().collect();
-        let points = parse_lines(&lines);
+        let input = crate::input(6, EXAMPLE);
+        let points = parse_input(input).unwrap();
         assert_eq!(get_max_area2(&points, 32), 16);
 
-        let input = include_str!("6.input");
-        let lines: Vec<_> = input.lines().collect();
-        let points = parse_lines(&lines);
+        let input = crate::input(6, INPUT);
+        let points = parse_input(input).unwrap();
         assert_eq!(get_max_area2(&points, 10000), 35237);
     }
 }
@@ -1,4 +1,7 @@
-use std::collections::{HashSet, VecDeque};
+use aoc_common::{
+    parse_lines, parse_with, Answer, Color, Dot, Drawing, ParseError, Parser, Solution, SolveError,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 #[derive(Debug)]
 struct Graph {
@@ -59,7 +62,7 @@ impl Graph {
 
 fn do_steps(graph: Graph, done: &[char]) -> Vec<char> {
     let mut graph = graph;
-    let mut done: Vec<_> = done.iter().cloned().collect();
+    let mut done: Vec<_> = done.to_vec();
 
     if let Some(c) = &graph.next_node().clone() {
         graph.remove_node(*c);
@@ -73,15 +76,21 @@ fn do_steps(graph: Graph, done: &[char]) -> Vec<char> {
 fn ordered_steps(pairs: &[(char, char)]) -> String {
     let graph = Graph::new(pairs);
 
-    let steps = do_steps(graph, &vec![]);
+    let steps = do_steps(graph, &[]);
     steps.iter().collect()
 }
 
 type Workers = Vec<VecDeque<char>>;
 
+/// Returns how long a step takes, which is its place in the alphabet plus
+/// the base time
+fn duration(c: char, base_time: i32) -> i32 {
+    (c as i32) - 64 + base_time
+}
+
 fn step_to_vec(c: char, time: i32) -> Vec<char> {
-    let count = (c as i32) - 64 + time;
-    std::iter::repeat(c).take(count as usize).collect()
+    let count = duration(c, time);
+    std::iter::repeat_n(c, count as usize).collect()
 }
 
 fn timed_steps(
@@ -91,7 +100,7 @@ fn timed_steps(
     done: &[char],
     time: u32,
 ) -> (Vec<char>, u32) {
-    let mut done: Vec<_> = done.iter().cloned().collect();
+    let mut done: Vec<_> = done.to_vec();
 
     // Process completed steps for all workers
     for worker in &mut workers {
@@ -117,16 +126,6 @@ fn timed_steps(
         }
     }
 
-    print!("{:3} ", time);
-    for worker in &workers {
-        let c = match worker.get(0) {
-            Some(c) => c,
-            None => &'.',
-        };
-        print!("{} ", c);
-    }
-    println!("{}", done.iter().collect::<String>());
-
     if !graph.is_empty() {
         timed_steps(graph, base_time, workers, &done, time + 1)
     } else {
@@ -136,56 +135,231 @@ fn timed_steps(
 
 fn step_time(pairs: &[(char, char)], nworkers: usize, base_time: i32) -> (String, u32) {
     let graph = Graph::new(pairs);
-    let workers = std::iter::repeat(VecDeque::new()).take(nworkers).collect();
+    let workers = std::iter::repeat_n(VecDeque::new(), nworkers).collect();
 
-    let (steps, time) = timed_steps(graph, base_time, workers, &vec![], 0);
+    let (steps, time) = timed_steps(graph, base_time, workers, &[], 0);
     (steps.into_iter().collect(), time)
 }
 
-fn parse_line(s: &str) -> (char, char) {
-    let fields: Vec<_> = s.split_whitespace().collect();
-    (
-        fields[1].chars().next().unwrap(),
-        fields[7].chars().next().unwrap(),
-    )
+/// Returns the longest chain of steps that each depend on the one before,
+/// and how long it takes. However many workers there are, the steps cannot
+/// be done any faster.
+fn critical_path(pairs: &[(char, char)], base_time: i32) -> (Vec<char>, i32) {
+    // each step finishes after its slowest prerequisite, and the order the
+    // steps are done in has every prerequisite first
+    let mut finish: HashMap<char, (i32, Option<char>)> = HashMap::new();
+    for c in ordered_steps(pairs).chars() {
+        let slowest = pairs
+            .iter()
+            .filter(|(_, after)| *after == c)
+            .map(|(before, _)| (finish[before].0, *before))
+            .max_by_key(|(time, before)| (*time, std::cmp::Reverse(*before)));
+        let start = slowest.map_or(0, |(time, _)| time);
+        finish.insert(c, (start + duration(c, base_time), slowest.map(|(_, b)| b)));
+    }
+
+    let last = finish
+        .iter()
+        .max_by_key(|(c, (time, _))| (*time, std::cmp::Reverse(**c)))
+        .map(|(c, _)| *c);
+    let mut path: Vec<_> = std::iter::successors(last, |c| finish[c].1).collect();
+    path.reverse();
+    let time = last.map_or(0, |c| finish[&c].0);
+    (path, time)
+}
+
+/// What to point out when drawing the steps
+enum Highlight {
+    /// Label each step with its place in the order the steps are done
+    Order,
+    /// Draw the critical path in red, timed with this base time
+    CriticalPath(i32),
+}
+
+/// The color of the critical path in the drawing
+const CRITICAL: Color = Color(255, 0, 0);
+
+/// Draws the steps with an edge from each step to the ones that depend on
+/// it
+fn draw(pairs: &[(char, char)], highlight: Option<Highlight>) -> Dot {
+    let graph = Graph::new(pairs);
+    let mut nodes: Vec<_> = graph.nodes.iter().cloned().collect();
+    nodes.sort();
+    let mut edges: Vec<_> = graph.edges.iter().cloned().collect();
+    edges.sort();
+
+    let order = ordered_steps(pairs);
+    let (path, _) = match highlight {
+        Some(Highlight::CriticalPath(base_time)) => critical_path(pairs, base_time),
+        _ => (vec![], 0),
+    };
+    let critical: HashSet<_> = path.windows(2).map(|pair| (pair[0], pair[1])).collect();
+    let color = CRITICAL.hex();
+    let style = |on_path: bool| -> Vec<(&str, &str)> {
+        if on_path {
+            vec![("color", &color), ("penwidth", "2")]
+        } else {
+            vec![]
+        }
+    };
+
+    let mut dot = Dot::new("steps");
+    for c in nodes {
+        let name = c.to_string();
+        match highlight {
+            Some(Highlight::Order) => {
+                let place = order.find(c).unwrap_or(0) + 1;
+                dot.node(&name, &[("label", &format!("{} ({})", c, place))]);
+            }
+            _ => dot.node(&name, &style(path.contains(&c))),
+        }
+    }
+    for (before, after) in edges {
+        let on_path = critical.contains(&(before, after));
+        dot.edge(&before.to_string(), &after.to_string(), &style(on_path));
+    }
+    dot
+}
+
+/// Parses a step letter
+fn step(p: &mut Parser) -> Result<char, ParseError> {
+    p.satisfy("a step letter", |c| {
+        Some(c).filter(char::is_ascii_uppercase)
+    })
+}
+
+/// Parses a line like "Step C must be finished before step A can begin."
+fn parse_line(s: &str) -> Result<(char, char), ParseError> {
+    parse_with(s, |p: &mut Parser| {
+        p.literal("Step ")?;
+        let before = step(p)?;
+        p.literal(" must be finished before step ")?;
+        let after = step(p)?;
+        p.literal(" can begin.")?;
+        Ok((before, after))
+    })
 }
 
-fn parse_lines(lines: &[&str]) -> Vec<(char, char)> {
-    lines.iter().map(|l| parse_line(l)).collect()
+fn parse_input(s: &str) -> Result<Vec<(char, char)>, ParseError> {
+    parse_lines(s, parse_line)
+}
+
+const WORKERS: usize = 5;
+const BASE_TIME: i32 = 60;
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(ordered_steps(&parse_input(input)?).into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        let (_, time) = step_time(&parse_input(input)?, WORKERS, BASE_TIME);
+        Ok(time.into())
+    }
+
+    fn draw(&self, input: &str) -> Result<Option<Drawing>, SolveError> {
+        let pairs = parse_input(input)?;
+        let dot = draw(&pairs, Some(Highlight::CriticalPath(BASE_TIME)));
+        Ok(Some(Drawing::Dot(dot)))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::{EXAMPLE, INPUT};
+
+    #[test]
+    fn test_parse_line() {
+        let tests = &[
+            (
+                "Step C must be finished before step A can begin.",
+                Ok(('C', 'A')),
+            ),
+            (
+                "Step C must be finished before step AB can begin.",
+                Err(ParseError::new("\" can begin.\"", "B").at_column(38)),
+            ),
+            (
+                "Step c must be finished before step A can begin.",
+                Err(ParseError::new("a step letter", "c").at_column(6)),
+            ),
+            (
+                "Step C must be done before step A can begin.",
+                Err(ParseError::new("\" must be finished before step \"", " must").at_column(7)),
+            ),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(&parse_line(input), expected);
+        }
+    }
 
     #[test]
     fn test_ordered_steps() {
-        let input = include_str!("7.example.input");
-        let lines: Vec<_> = input.lines().collect();
-        let pairs = parse_lines(&lines);
+        let input = crate::input(7, EXAMPLE);
+        let pairs = parse_input(input).unwrap();
         let steps = ordered_steps(&pairs);
         assert_eq!(steps, "CABDFE");
 
-        let input = include_str!("7.input");
-        let lines: Vec<_> = input.lines().collect();
-        let pairs = parse_lines(&lines);
+        let input = crate::input(7, INPUT);
+        let pairs = parse_input(input).unwrap();
         let steps = ordered_steps(&pairs);
         assert_eq!(steps, "ABGKCMVWYDEHFOPQUILSTNZRJX");
     }
 
     #[test]
     fn test_step_time() {
-        let input = include_str!("7.example.input");
-        let lines: Vec<_> = input.lines().collect();
-        let pairs = parse_lines(&lines);
+        let input = crate::input(7, EXAMPLE);
+        let pairs = parse_input(input).unwrap();
         let (steps, time) = step_time(&pairs, 2, 0);
         assert_eq!(steps, "CABFDE");
         assert_eq!(time, 15);
 
-        let input = include_str!("7.input");
-        let lines: Vec<_> = input.lines().collect();
-        let pairs = parse_lines(&lines);
+        let input = crate::input(7, INPUT);
+        let pairs = parse_input(input).unwrap();
         let (_, time) = step_time(&pairs, 5, 60);
         assert_eq!(time, 898);
     }
+
+    #[test]
+    fn test_critical_path() {
+        let pairs = parse_input(crate::input(7, EXAMPLE)).unwrap();
+        assert_eq!(critical_path(&pairs, 0), (vec!['C', 'F', 'E'], 14));
+        assert_eq!(critical_path(&pairs, 60), (vec!['C', 'A', 'D', 'E'], 253));
+        assert_eq!(critical_path(&[], 0), (vec![], 0));
+    }
+
+    #[test]
+    fn test_draw() {
+        let pairs = parse_input(crate::input(7, EXAMPLE)).unwrap();
+        let dot = draw(&pairs, Some(Highlight::CriticalPath(0))).to_string();
+        let expected = r##"digraph "steps" {
+  "A";
+  "B";
+  "C" [color="#ff0000", penwidth="2"];
+  "D";
+  "E" [color="#ff0000", penwidth="2"];
+  "F" [color="#ff0000", penwidth="2"];
+  "A" -> "B";
+  "A" -> "D";
+  "B" -> "E";
+  "C" -> "A";
+  "C" -> "F" [color="#ff0000", penwidth="2"];
+  "D" -> "E";
+  "F" -> "E" [color="#ff0000", penwidth="2"];
+}
+"##;
+        assert_eq!(dot, expected);
+
+        let dot = draw(&pairs, Some(Highlight::Order)).to_string();
+        assert!(dot.contains("\"C\" [label=\"C (1)\"];"));
+        assert!(dot.contains("\"E\" [label=\"E (6)\"];"));
+        assert_eq!(draw(&pairs, None).len(), 13);
+    }
 }
@@ -1,3 +1,5 @@
+use aoc_common::{parse_number, Answer, ParseError, Solution, SolveError};
+
 #[derive(Debug)]
 struct Node {
     meta: Vec<usize>,
@@ -61,35 +63,59 @@ fn process_tree(ns: &[usize]) -> Node {
     node
 }
 
-fn parse_input(s: &str) -> Vec<usize> {
-    s.split_whitespace()
-        .map(|n| n.parse::<usize>().unwrap())
-        .collect()
+fn parse_input(s: &str) -> Result<Vec<usize>, ParseError> {
+    s.split_whitespace().map(parse_number).collect()
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(process_tree(&parse_input(input)?).checksum().into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(process_tree(&parse_input(input)?).checksum_2().into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::{EXAMPLE, INPUT};
+
+    #[test]
+    fn test_parse_input() {
+        assert_eq!(parse_input("2 3 0\n"), Ok(vec![2, 3, 0]));
+        assert_eq!(
+            parse_input("2 3 x 0"),
+            Err(ParseError::new("a number", "x"))
+        );
+    }
 
     #[test]
     fn test_checksum() {
-        let input = include_str!("8.example.input");
-        let nums = parse_input(&input);
+        let input = crate::input(8, EXAMPLE);
+        let nums = parse_input(input).unwrap();
         assert_eq!(process_tree(&nums).checksum(), 138);
 
-        let input = include_str!("8.input");
-        let nums = parse_input(&input);
+        let input = crate::input(8, INPUT);
+        let nums = parse_input(input).unwrap();
         assert_eq!(process_tree(&nums).checksum(), 46781);
     }
 
     #[test]
     fn test_checksum_2() {
-        let input = include_str!("8.example.input");
-        let nums = parse_input(&input);
+        let input = crate::input(8, EXAMPLE);
+        let nums = parse_input(input).unwrap();
         assert_eq!(process_tree(&nums).checksum_2(), 66);
 
-        let input = include_str!("8.input");
-        let nums = parse_input(&input);
+        let input = crate::input(8, INPUT);
+        let nums = parse_input(input).unwrap();
         assert_eq!(process_tree(&nums).checksum_2(), 21405);
     }
 }
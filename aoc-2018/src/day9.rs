@@ -1,3 +1,5 @@
+use aoc_common::{parse_with, Answer, ParseError, Parser, Solution, SolveError};
+
 fn shift_idx(len: usize, shift: i32) -> usize {
     if shift < 0 {
         let mut idx = shift;
@@ -10,7 +12,7 @@ fn shift_idx(len: usize, shift: i32) -> usize {
         while idx >= len {
             idx -= len
         }
-        idx as usize
+        idx
     }
 }
 
@@ -36,7 +38,7 @@ impl Game {
 
     fn next(&mut self) {
         self.marble += 1;
-        if self.marble % 23 == 0 {
+        if self.marble.is_multiple_of(23) {
             self.scores[self.current_player] += self.marble;
             self.current = shift_idx(self.circle.len(), self.current as i32 - 7);
             self.scores[self.current_player] += self.circle.remove(self.current);
@@ -62,15 +64,61 @@ fn high_score(players: usize, last: usize) -> usize {
     *game.scores.iter().max().unwrap()
 }
 
-fn parse_input(s: &str) -> (usize, usize) {
-    let tokens: Vec<_> = s.split_whitespace().collect();
-    (tokens[0].parse().unwrap(), tokens[6].parse().unwrap())
+/// Parses a line like "9 players; last marble is worth 25 points"
+fn parse_input(s: &str) -> Result<(usize, usize), ParseError> {
+    parse_with(s.trim_end(), |p: &mut Parser| {
+        let players = p.unsigned()?;
+        p.literal(" players; last marble is worth ")?;
+        let last = p.unsigned()?;
+        p.literal(" points")?;
+        Ok((players, last))
+    })
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        let (players, last) = parse_input(input)?;
+        Ok(high_score(players, last).into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        // "What would the new winning Elf's score be if the number of the last
+        // marble were 100 times larger?"
+        let (players, last) = parse_input(input)?;
+        Ok(high_score(players, last * 100).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_input() {
+        let tests = &[
+            ("9 players; last marble is worth 25 points\n", Ok((9, 25))),
+            (
+                "9 players; last marble is worth 2x5 points",
+                Err(ParseError::new("\" points\"", "x5").at_column(34)),
+            ),
+            (
+                "9 players; last marble is 25",
+                Err(
+                    ParseError::new("\" players; last marble is worth \"", " players").at_column(2),
+                ),
+            ),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(&parse_input(input), expected);
+        }
+    }
+
     #[test]
     fn test_high_score() {
         let tests = [
@@ -89,7 +137,7 @@ mod tests {
             ),
         ];
         for (input, score) in &tests {
-            let (players, last) = parse_input(input);
+            let (players, last) = parse_input(input).unwrap();
             assert_eq!(high_score(players, last), *score);
         }
     }
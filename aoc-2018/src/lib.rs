@@ -22,3 +22,123 @@ mod day7;
 mod day8;
 #[allow(dead_code)]
 mod day9;
+
+use aoc_common::{Registry, EXAMPLE, INPUT};
+
+pub const YEAR: u32 = 2018;
+
+/// Adds the solution for each day to the registry
+pub fn register(registry: &mut Registry) {
+    registry.register(YEAR, 1, day1::Puzzle);
+    registry.register(YEAR, 2, day2::Puzzle);
+    registry.register(YEAR, 3, day3::Puzzle);
+    registry.register(YEAR, 4, day4::Puzzle);
+    registry.register(YEAR, 5, day5::Puzzle);
+    registry.register(YEAR, 6, day6::Puzzle);
+    registry.register(YEAR, 7, day7::Puzzle);
+    registry.register(YEAR, 8, day8::Puzzle);
+    registry.register(YEAR, 9, day9::Puzzle);
+    registry.register(YEAR, 10, day10::Puzzle);
+    registry.register(YEAR, 11, day11::Puzzle);
+    registry.register(YEAR, 12, day12::Puzzle);
+
+    registry.add_input(YEAR, 1, INPUT, include_str!("1.input"));
+    registry.add_input(YEAR, 2, INPUT, include_str!("2.input"));
+    registry.add_input(YEAR, 3, INPUT, include_str!("3.input"));
+    registry.add_input(YEAR, 4, INPUT, include_str!("4.input"));
+    registry.add_input(YEAR, 4, EXAMPLE, include_str!("4.example.input"));
+    registry.add_input(YEAR, 5, INPUT, include_str!("5.input"));
+    registry.add_input(YEAR, 6, INPUT, include_str!("6.input"));
+    registry.add_input(YEAR, 6, EXAMPLE, include_str!("6.example.input"));
+    registry.add_input(YEAR, 6, "example2", include_str!("6.example2.input"));
+    registry.add_input(YEAR, 7, INPUT, include_str!("7.input"));
+    registry.add_input(YEAR, 7, EXAMPLE, include_str!("7.example.input"));
+    registry.add_input(YEAR, 8, INPUT, include_str!("8.input"));
+    registry.add_input(YEAR, 8, EXAMPLE, include_str!("8.example.input"));
+    registry.add_input(
+        YEAR,
+        9,
+        INPUT,
+        "403 players; last marble is worth 71920 points",
+    );
+    registry.add_input(YEAR, 10, INPUT, include_str!("10.input"));
+    registry.add_input(YEAR, 10, EXAMPLE, include_str!("10.example.input"));
+    registry.add_expected(YEAR, 10, INPUT, include_str!("10.solution"));
+    registry.add_expected(YEAR, 10, EXAMPLE, include_str!("10.example.solution"));
+    registry.add_input(YEAR, 11, INPUT, "8772");
+    registry.add_input(YEAR, 12, INPUT, include_str!("12.input"));
+    registry.add_input(YEAR, 12, EXAMPLE, include_str!("12.example.input"));
+}
+
+#[cfg(test)]
+thread_local! {
+    /// The registry the tests read embedded inputs from
+    static REGISTRY: Registry = {
+        let mut registry = Registry::new();
+        register(&mut registry);
+        registry
+    };
+}
+
+/// Returns a named embedded input for a day. The tests check the answers
+/// for these inputs, so they never read the input cache.
+#[cfg(test)]
+fn input(day: u32, name: &str) -> &'static str {
+    REGISTRY
+        .with(|registry| registry.input(YEAR, day, name))
+        .unwrap_or_else(|| panic!("no {} for {} day {}", name, YEAR, day))
+}
+
+/// Returns the embedded expected output for a named input, like `input`
+#[cfg(test)]
+fn expected(day: u32, name: &str) -> &'static str {
+    REGISTRY
+        .with(|registry| registry.expected(YEAR, day, name))
+        .unwrap_or_else(|| panic!("no expected output for {} {} day {}", name, YEAR, day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::{Answer, SolveError};
+
+    #[test]
+    fn test_register() {
+        let mut registry = Registry::new();
+        register(&mut registry);
+        let days: Vec<_> = registry.days().map(|(_, day)| day).collect();
+        assert_eq!(days, (1..=12).collect::<Vec<_>>());
+
+        let tests = &[
+            (1, (Answer::Number(543), Answer::Number(621))),
+            (
+                7,
+                (
+                    Answer::from("ABGKCMVWYDEHFOPQUILSTNZRJX"),
+                    Answer::Number(898),
+                ),
+            ),
+            (10, (Answer::from("EJZEAAPE"), Answer::Number(10054))),
+            (12, (Answer::Number(3241), Answer::Number(2749999999911))),
+        ];
+        for (day, (part1, part2)) in tests {
+            let solution = registry.get(YEAR, *day).unwrap();
+            let input = registry.input(YEAR, *day, INPUT).unwrap();
+            assert_eq!(solution.part1(input).as_ref(), Ok(part1));
+            assert_eq!(solution.part2(input).as_ref(), Ok(part2));
+        }
+
+        let solution = registry.get(YEAR, 7).unwrap();
+        let input = registry.input(YEAR, 7, EXAMPLE).unwrap();
+        assert_eq!(solution.part1(input), Ok(Answer::from("CABDFE")));
+        let err = solution.part1("Step C must be finished before step A can begin.\nStep C\n");
+        match err.unwrap_err() {
+            SolveError::Parse(err) => assert_eq!(err.line(), Some(2)),
+            err => panic!("expected a parse error, got {:?}", err),
+        }
+        assert_eq!(
+            registry.input_names(YEAR, 6),
+            vec![EXAMPLE, "example2", INPUT]
+        );
+    }
+}
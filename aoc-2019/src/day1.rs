@@ -1,5 +1,7 @@
 // https://adventofcode.com/2019/day/1
 
+use aoc_common::{parse_lines, parse_number, Answer, ParseError, Solution, SolveError};
+
 fn fuel(mass: i32) -> i32 {
     let f = mass / 3 - 2;
     if f == 0 {
@@ -12,7 +14,7 @@ fn fuel(mass: i32) -> i32 {
 fn fuel_recursive_2(mass: i32) -> i32 {
     let mut f = fuel(mass);
     let mut ff = fuel(f);
-    while { ff > 0 } {
+    while ff > 0 {
         f += ff;
         ff = fuel(ff)
     }
@@ -27,12 +29,38 @@ fn fuel_recursive(mass: i32) -> i32 {
     f + fuel_recursive(f)
 }
 
+fn parse_input(s: &str) -> Result<Vec<i32>, ParseError> {
+    parse_lines(s, parse_number)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(parse_input(input)?
+            .into_iter()
+            .map(fuel)
+            .sum::<i32>()
+            .into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        let masses = parse_input(input)?.into_iter();
+        Ok(masses.map(fuel_recursive).sum::<i32>().into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::INPUT;
 
     fn input() -> impl Iterator<Item = i32> {
-        let input = include_str!("day1.input");
+        let input = crate::input(1, INPUT);
         input.lines().map(|line| line.parse::<i32>().unwrap())
     }
 
@@ -43,7 +71,7 @@ mod tests {
             assert_eq!(&fuel(*input), expected);
         }
 
-        let total_fuel: i32 = input().map(|mass| fuel(mass)).sum();
+        let total_fuel: i32 = input().map(fuel).sum();
         assert_eq!(total_fuel, 3373568);
     }
 
@@ -55,7 +83,7 @@ mod tests {
             assert_eq!(&fuel_recursive_2(*input), expected);
         }
 
-        let total_fuel: i32 = input().map(|mass| fuel_recursive(mass)).sum();
+        let total_fuel: i32 = input().map(fuel_recursive).sum();
         assert_eq!(total_fuel, 5057481);
     }
 }
@@ -1,9 +1,9 @@
 // https://adventofcode.com/2019/day/10
 
+use aoc_common::{Answer, Color, Drawing, Grid, ParseError, Point, Solution, SolveError};
 use num::rational::Rational;
 use std::collections::{HashMap, HashSet};
 use std::fmt;
-use std::ops;
 
 /// Quadrant represent the four quadrants, starting with the top-right, and
 /// going clock-wise
@@ -26,19 +26,15 @@ struct Angle {
     grade: Rational,
 }
 
-/// A point represents X, Y coordinates
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct Point(isize, isize);
-
-impl ops::Sub for Point {
-    type Output = Point;
-
-    fn sub(self, other: Point) -> Point {
-        Point(self.0 - other.0, self.1 - other.1)
-    }
+/// Angles and distances between points
+trait Polar {
+    fn quadrant(&self) -> Quadrant;
+    fn angle(&self) -> Angle;
+    fn angle_to(&self, other: &Self) -> Angle;
+    fn sq_dist_to(&self, other: &Self) -> isize;
 }
 
-impl Point {
+impl Polar for Point<isize> {
     fn quadrant(&self) -> Quadrant {
         match self {
             Point(x, y) if *x >= 0 && *y < 0 => Quadrant::One,
@@ -59,64 +55,79 @@ impl Point {
         Angle { quadrant, grade }
     }
 
-    fn angle_to(&self, other: &Point) -> Angle {
-        let point = other.clone() - self.clone();
+    fn angle_to(&self, other: &Self) -> Angle {
+        let point = *other - *self;
         point.angle()
     }
 
-    fn sq_dist_to(&self, other: &Point) -> isize {
-        let point = other.clone() - self.clone();
+    fn sq_dist_to(&self, other: &Self) -> isize {
+        let point = *other - *self;
         point.0 * point.0 + point.1 * point.1
     }
 }
 
 /// A map is a list of points
 #[derive(Debug)]
-struct Map(Vec<Point>);
+struct Map(Vec<Point<isize>>);
 
 impl fmt::Display for Map {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let grid = self.to_grid();
+        write!(
+            f,
+            "{}",
+            grid.render(|asteroid| if *asteroid { '#' } else { '.' })
+        )
+    }
+}
+
+impl Map {
+    /// Returns a grid just big enough to hold the asteroids, with the
+    /// asteroids set
+    fn to_grid(&self) -> Grid<bool> {
         let max_x = self.0.iter().map(|Point(x, _y)| *x).max().unwrap_or(0);
         let max_y = self.0.iter().map(|Point(_x, y)| *y).max().unwrap_or(0);
-        let mut lines: Vec<String> = Vec::new();
-        for y in 0..=max_y {
-            let line: String = (0..=max_x)
-                .map(|x| {
-                    if self.0.contains(&Point(x, y)) {
-                        '#'
-                    } else {
-                        '.'
-                    }
-                })
-                .collect();
-            lines.push(line);
+        let mut grid = Grid::new((max_x + 1) as usize, (max_y + 1) as usize, false);
+        for Point(x, y) in &self.0 {
+            grid[Point(*x as usize, *y as usize)] = true;
         }
-        write!(f, "{}", lines.join("\n"))
+        grid
     }
-}
 
-impl Map {
-    fn parse(s: &str) -> Self {
-        let points: Vec<Point> = s
-            .lines()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.chars()
-                    .enumerate()
-                    .flat_map(|(x, c)| {
-                        if c == '#' {
-                            Some(Point(x as isize, y as isize))
-                        } else {
-                            None
-                        }
-                    })
-                    .collect::<Vec<_>>()
-            })
+    /// Draws the asteroids in white, the monitoring station in red, and
+    /// the asteroid the bet is on in yellow
+    fn image(&self) -> Grid<Color> {
+        let mut image = self.to_grid().map(|asteroid| {
+            if *asteroid {
+                Color::WHITE
+            } else {
+                Color::BLACK
+            }
+        });
+        if let Some((base, _)) = self.best_point() {
+            if let Some(Point(x, y)) = self.vaporize_order(&base).get(VAPORIZED - 1) {
+                image[Point(*x as usize, *y as usize)] = Color(255, 255, 0);
+            }
+            image[Point(base.0 as usize, base.1 as usize)] = Color(255, 0, 0);
+        }
+        image
+    }
+
+    fn parse(s: &str) -> Result<Self, ParseError> {
+        let grid = Grid::parse(s, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?;
+        let points = grid
+            .iter()
+            .filter(|(_, asteroid)| **asteroid)
+            .map(|(Point(x, y), _)| Point(x as isize, y as isize))
             .collect();
-        Map(points)
+        Ok(Map(points))
     }
 
-    fn angles(&self, other: &Point) -> HashSet<Angle> {
+    fn angles(&self, other: &Point<isize>) -> HashSet<Angle> {
         self.0
             .iter()
             .filter(|point| *point != other)
@@ -124,31 +135,92 @@ impl Map {
             .collect()
     }
 
-    fn best_point(&self) -> Option<(Point, usize)> {
+    fn best_point(&self) -> Option<(Point<isize>, usize)> {
         self.0
             .iter()
-            .map(|point| (point.clone(), self.angles(point).len()))
+            .map(|point| (*point, self.angles(point).len()))
             .max_by_key(|point_num| point_num.1)
     }
 
-    fn angle_distance(&self, other: &Point) -> Vec<(Point, Angle, isize)> {
+    fn angle_distance(&self, other: &Point<isize>) -> Vec<(Point<isize>, Angle, isize)> {
         self.0
             .iter()
             .filter(|point| *point != other)
-            .map(|point| {
-                (
-                    point.clone(),
-                    other.angle_to(point),
-                    point.sq_dist_to(other),
-                )
-            })
+            .map(|point| (*point, other.angle_to(point), point.sq_dist_to(other)))
             .collect()
     }
+
+    /// Returns the points in the order they are vaporized by a laser at
+    /// `base`. The laser starts pointing up and rotates clockwise, hitting
+    /// only the closest point at each angle per rotation.
+    fn vaporize_order(&self, base: &Point<isize>) -> Vec<Point<isize>> {
+        let mut points = self.0.clone();
+        points.sort_by_key(|point| base.sq_dist_to(point));
+
+        let mut angle_points: HashMap<Angle, Vec<Point<isize>>> = HashMap::new();
+        for point in &points {
+            if point == base {
+                continue;
+            }
+            let angle = base.angle_to(point);
+            let entry = angle_points.entry(angle).or_default();
+            (*entry).push(*point);
+        }
+
+        let mut angle_points: Vec<_> = angle_points.into_iter().collect();
+        angle_points.sort_by_key(|(angle, _points)| angle.clone());
+
+        let point_lists: Vec<_> = angle_points
+            .into_iter()
+            .map(|(_angle, points)| points)
+            .collect();
+
+        let longest = point_lists.iter().map(|ps| ps.len()).max().unwrap_or(0);
+        let mut ordered_points = Vec::new();
+        for i in 0..longest {
+            for ps in &point_lists {
+                if let Some(point) = ps.get(i) {
+                    ordered_points.push(*point);
+                }
+            }
+        }
+        ordered_points
+    }
+}
+
+/// The part 2 answer is for the asteroid vaporized in this position
+const VAPORIZED: usize = 200;
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        Map::parse(input.trim_end()).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        let map = Map::parse(input.trim_end())?;
+        Ok(map.best_point().map(|(_, count)| count).into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        let map = Map::parse(input.trim_end())?;
+        Ok(map
+            .best_point()
+            .and_then(|(base, _)| map.vaporize_order(&base).get(VAPORIZED - 1).cloned())
+            .map(|Point(x, y)| x * 100 + y)
+            .into())
+    }
+
+    fn draw(&self, input: &str) -> Result<Option<Drawing>, SolveError> {
+        Ok(Some(Drawing::Image(Map::parse(input.trim_end())?.image())))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::INPUT;
 
     #[test]
     fn test() {
@@ -212,7 +284,7 @@ mod tests {
 #.#.#.#####.####.###
 ###.##.####.##.#..##";
 
-        let my_input = include_str!("day10.input").trim_end();
+        let my_input = crate::input(10, INPUT).trim_end();
 
         let tests = &[
             (input_1, (Point(3, 4), 8)),
@@ -223,7 +295,7 @@ mod tests {
             (my_input, (Point(11, 13), 227)),
         ];
         for (input, expected) in tests {
-            let map = Map::parse(input);
+            let map = Map::parse(input).unwrap();
             assert_eq!(&map.to_string(), input);
             assert_eq!(&map.best_point().unwrap(), expected);
         }
@@ -302,7 +374,7 @@ mod tests {
 ##...##.#####..##
 ##...#...#.#####.
 ..#.....#...###..
- ..#.#.....#....##";
+..#.#.....#....##";
 
         let input_2 = ".#..##.###...#######
 ##.############..##.
@@ -325,7 +397,7 @@ mod tests {
 #.#.#.#####.####.###
 ###.##.####.##.#..##";
 
-        let my_input = include_str!("day10.input").trim_end();
+        let my_input = crate::input(10, INPUT).trim_end();
 
         let tests = &[
             ((input_1, Point(8, 3)), None),
@@ -334,41 +406,10 @@ mod tests {
         ];
         for (input, expected) in tests {
             let (input, base) = input;
-            let map = Map::parse(input);
-
-            let mut points = map.0;
-            points.sort_by_key(|point| base.sq_dist_to(point));
-
-            let mut angle_points: HashMap<Angle, Vec<Point>> = HashMap::new();
-            for point in &points {
-                if point == base {
-                    continue;
-                }
-                let angle = base.angle_to(point);
-                let entry = angle_points.entry(angle).or_insert(Vec::new());
-                (*entry).push(point.clone());
-            }
-
-            let mut angle_points: Vec<_> = angle_points.into_iter().map(|(k, v)| (k, v)).collect();
-            angle_points.sort_by_key(|(angle, _points)| angle.clone());
-
-            let point_lists: Vec<_> = angle_points
-                .into_iter()
-                .map(|(_angle, points)| points)
-                .collect();
-
-            let longest = point_lists.iter().map(|ps| ps.len()).max().unwrap_or(0);
-            let mut ordered_points = Vec::new();
-            for i in 0..longest {
-                for ps in &point_lists {
-                    if let Some(point) = ps.get(i) {
-                        ordered_points.push(point);
-                    }
-                }
-            }
+            let map = Map::parse(input).unwrap();
 
-            println!("{:?}", ordered_points);
-            assert_eq!(ordered_points.get(200 - 1).cloned(), expected.as_ref());
+            let ordered_points = map.vaporize_order(base);
+            assert_eq!(ordered_points.get(200 - 1), expected.as_ref());
         }
     }
 }
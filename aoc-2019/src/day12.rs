@@ -1,5 +1,9 @@
 // https://adventofcode.com/2019/day/12
 
+use aoc_common::{
+    brent, parse_lines, parse_with, Answer, ParseError, Parser, Solution, SolveError,
+};
+use num::integer::lcm;
 use std::fmt;
 use std::ops::Add;
 
@@ -20,18 +24,18 @@ impl fmt::Display for Position {
     }
 }
 
-fn parse_position(s: &str) -> Position {
-    let s = s.trim_start_matches('<').trim_end_matches('>');
-    let parts = s.split(',').map(|s| s.trim());
-    let parts: Vec<_> = parts
-        .map(|s| s.splitn(2, '=').last())
-        .flatten()
-        .map(|s| s.trim().parse::<i32>().unwrap())
-        .collect();
-    match parts.as_slice() {
-        [x, y, z] => Position(*x, *y, *z),
-        _ => panic!("invalid position"),
-    }
+/// Parses a position like "<x=-1, y=0, z=2>"
+fn parse_position(s: &str) -> Result<Position, ParseError> {
+    parse_with(s, |p: &mut Parser| {
+        p.literal("<x=")?;
+        let x = p.signed()?;
+        p.literal(", y=")?;
+        let y = p.signed()?;
+        p.literal(", z=")?;
+        let z = p.signed()?;
+        p.literal(">")?;
+        Ok(Position(x, y, z))
+    })
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -59,11 +63,8 @@ impl Moon {
     }
 }
 
-fn parse_input(s: &str) -> Vec<Moon> {
-    s.lines()
-        .map(|s| s.trim())
-        .map(|s| Moon::new(parse_position(s)))
-        .collect()
+fn parse_input(s: &str) -> Result<Vec<Moon>, ParseError> {
+    parse_lines(s, |line| Ok(Moon::new(parse_position(line.trim())?)))
 }
 
 fn calc_velocity(p1: i32, p2: i32) -> (i32, i32) {
@@ -120,19 +121,60 @@ fn simulate(moons: Vec<Moon>) -> impl Iterator<Item = Vec<Moon>> {
     std::iter::successors(Some(moons), |moons| Some(step_time(moons)))
 }
 
+/// Steps one axis of the simulation, where each moon is a (position,
+/// velocity) pair
+fn step_axis(axis: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    axis.iter()
+        .map(|(p1, v)| {
+            let dv: i32 = axis.iter().map(|(p2, _)| calc_velocity(*p1, *p2).0).sum();
+            (p1 + v + dv, v + dv)
+        })
+        .collect()
+}
+
+/// Returns the number of steps until the moons are back where they started.
+/// The axes move independently, so each axis has its own period, and they
+/// all line up at the least common multiple. Each step can be reversed, so
+/// every state is part of the cycle.
 fn calc_steps_to_repeat(moons: Vec<Moon>) -> usize {
-    let start: Vec<_> = moons.clone();
-    let sim = simulate(moons);
-    sim.enumerate()
-        .skip(1)
-        .find(|(_i, moons)| moons == &start)
-        .map(|(i, _moons)| i)
-        .unwrap()
+    let axes: [fn(&Position) -> i32; 3] = [|p| p.0, |p| p.1, |p| p.2];
+    axes.iter()
+        .map(|coord| {
+            let axis: Vec<_> = moons
+                .iter()
+                .map(|moon| (coord(&moon.position), coord(&moon.velocity)))
+                .collect();
+            brent(axis, |axis| step_axis(axis)).period
+        })
+        .fold(1, lcm)
+}
+
+/// Part 1 measures the energy after this many steps
+const STEPS: usize = 1000;
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(simulate(parse_input(input)?)
+            .nth(STEPS)
+            .map(|moons| total_energy(&moons))
+            .into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(calc_steps_to_repeat(parse_input(input)?).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::INPUT;
 
     const INPUT_1: &str = "<x=-1, y=0, z=2>
 <x=2, y=-10, z=-7>
@@ -144,13 +186,14 @@ mod tests {
 <x=2, y=-7, z=3>
 <x=9, y=-8, z=-3>";
 
-    const MY_INPUT: &str = include_str!("day12.input");
-
     #[test]
     fn test_part_1() {
-        let tests = &[((INPUT_1, 10), 179), ((MY_INPUT, 1000), 5937)];
+        let tests = &[
+            ((INPUT_1, 10), 179),
+            ((crate::input(12, INPUT), 1000), 5937),
+        ];
         for ((input, step), expected) in tests {
-            let moons = parse_input(input);
+            let moons = parse_input(input).unwrap();
             let mut sim = simulate(moons);
             let moons = sim.nth(*step).unwrap();
             let energy = total_energy(&moons);
@@ -160,21 +203,31 @@ mod tests {
 
     #[test]
     fn test_part_2_ex_1() {
-        let moons = parse_input(INPUT_1);
+        let moons = parse_input(INPUT_1).unwrap();
         assert_eq!(calc_steps_to_repeat(moons), 2772);
     }
 
     #[test]
-    #[ignore]
     fn test_part_2_ex_2() {
-        let moons = parse_input(INPUT_2);
+        let moons = parse_input(INPUT_2).unwrap();
         assert_eq!(calc_steps_to_repeat(moons), 4686774924);
     }
 
     #[test]
-    #[ignore]
     fn test_part_2_my_input() {
-        let moons = parse_input(MY_INPUT);
-        assert_eq!(calc_steps_to_repeat(moons), 0);
+        let moons = parse_input(crate::input(12, INPUT)).unwrap();
+        assert_eq!(calc_steps_to_repeat(moons), 376203951569712);
+    }
+
+    #[test]
+    fn test_parse_position() {
+        assert_eq!(parse_position("<x=-1, y=0, z=2>"), Ok(Position(-1, 0, 2)));
+        let expected = ParseError::new("\", y=\"", ",").at_column(5);
+        assert_eq!(parse_position("<x=1, z=2, y=0>"), Err(expected));
+        let expected = ParseError::new("a number", "a").at_column(4).at_line(2);
+        assert_eq!(
+            parse_input("<x=-1, y=0, z=2>\n<x=a, y=0, z=2>").unwrap_err(),
+            expected
+        );
     }
 }
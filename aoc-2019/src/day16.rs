@@ -1,5 +1,6 @@
 // https://adventofcode.com/2019/day/16
 
+use aoc_common::{parse_digits, Answer, ParseError, Solution, SolveError};
 use num::Integer;
 
 const BASE_PATTERN: &[i32] = &[0, 1, 0, -1];
@@ -9,7 +10,7 @@ fn pattern(repeat: usize) -> impl Iterator<Item = i32> {
     BASE_PATTERN
         .iter()
         .cloned()
-        .flat_map(move |n| std::iter::repeat(n).take(repeat))
+        .flat_map(move |n| std::iter::repeat_n(n, repeat))
         .cycle()
 }
 
@@ -18,8 +19,8 @@ fn ones(n: i32) -> i32 {
     (n % 10).abs()
 }
 
-fn parse_input(s: &str) -> Vec<u32> {
-    s.trim().chars().map(|c| c.to_digit(10).unwrap()).collect()
+fn parse_input(s: &str) -> Result<Vec<u32>, ParseError> {
+    parse_digits(s)
 }
 
 fn transform_value<'a, T, U>(input: T, pattern: U) -> i32
@@ -61,7 +62,7 @@ fn transform_2(input: &[u32], times: usize) -> Vec<u32> {
             total_len,
             repeat,
         );
-        let input = std::iter::repeat(input.iter()).take(repeat).flatten();
+        let input = std::iter::repeat_n(input.iter(), repeat).flatten();
         let value = transform_value(input, pattern(i + 1).skip(1));
         output.push(value as u32);
     }
@@ -70,11 +71,11 @@ fn transform_2(input: &[u32], times: usize) -> Vec<u32> {
 }
 
 fn transform_iter(input: Vec<u32>) -> impl Iterator<Item = Vec<u32>> {
-    std::iter::successors(Some(input), |input| Some(transform(&input)))
+    std::iter::successors(Some(input), |input| Some(transform(input)))
 }
 
 fn transform_iter_2(input: Vec<u32>, times: usize) -> impl Iterator<Item = Vec<u32>> {
-    std::iter::successors(Some(input), move |input| Some(transform_2(&input, times)))
+    std::iter::successors(Some(input), move |input| Some(transform_2(input, times)))
 }
 
 fn calc_offset(input: &[u32]) -> usize {
@@ -86,9 +87,34 @@ fn least_common_multiple(n1: usize, n2: usize) -> usize {
     n1.lcm(&n2)
 }
 
+/// The number of phases to run
+const PHASES: usize = 100;
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(transform_iter(parse_input(input)?)
+            .nth(PHASES)
+            .map(|output| {
+                output
+                    .iter()
+                    .take(8)
+                    .map(|n| n.to_string())
+                    .collect::<String>()
+            })
+            .into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::INPUT;
 
     #[test]
     fn test_ones() {
@@ -110,7 +136,7 @@ mod tests {
     fn test_parse_input() {
         let tests = &[("", vec![]), ("012", vec![0, 1, 2])];
         for (input, expected) in tests {
-            assert_eq!(&parse_input(input), expected);
+            assert_eq!(parse_input(input).as_ref(), Ok(expected));
         }
     }
 
@@ -134,12 +160,12 @@ mod tests {
     fn test_transform() {
         let tests = &[
             ("80871224585914546619083218645595", "24176176"),
-            (include_str!("day16.input"), "68764632"),
+            (crate::input(16, INPUT), "68764632"),
         ];
         for (input, expected) in tests {
-            let input = parse_input(input);
-            let input = transform_iter(input).skip(100).next().unwrap();
-            let expected = parse_input(expected);
+            let input = parse_input(input).unwrap();
+            let input = transform_iter(input).nth(100).unwrap();
+            let expected = parse_input(expected).unwrap();
             assert_eq!(input[0..8].to_vec(), expected);
         }
     }
@@ -147,7 +173,7 @@ mod tests {
     #[test]
     fn test_calc_offset() {
         let input = "1234567";
-        let input = parse_input(input);
+        let input = parse_input(input).unwrap();
         let offset = calc_offset(&input);
         assert_eq!(offset, 1234567)
     }
@@ -159,13 +185,12 @@ mod tests {
         let tests = &[
             ("12345678", "01029498"),
             ("03036732577212944063491565474664", "84462026"),
-            (include_str!("day16.input"), ""),
         ];
         for (input, expected) in tests {
-            let input = parse_input(input);
-            let input = transform_iter_2(input, 10).skip(100).next().unwrap();
+            let input = parse_input(input).unwrap();
+            let input = transform_iter_2(input, 10).nth(100).unwrap();
             let offset = calc_offset(&input);
-            let expected = parse_input(expected);
+            let expected = parse_input(expected).unwrap();
 
             assert_eq!(input[offset..offset + 8].to_vec(), expected);
         }
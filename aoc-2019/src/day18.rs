@@ -1,74 +1,64 @@
 // https://adventofcode.com/2019/day/18
 
-use std::collections::HashSet;
+use aoc_common::{Grid, ParseError};
 
 #[derive(Debug, PartialEq, Eq, Hash)]
 enum State {
+    Wall,
     Empty,
     Start,
     Key(char),
     Door(char),
 }
 
-#[derive(Debug, PartialEq, Eq, Hash)]
-struct Point(usize, usize);
-
-#[derive(Debug)]
-struct Graph(Vec<(Point, State)>);
-
-impl Graph {
-    fn new(input: &str) -> Self {
-        let points: Vec<(Point, State)> = input
-            .lines()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.chars()
-                    .enumerate()
-                    .filter(|(_x, c)| *c != '#')
-                    .map(move |(x, _c)| (Point(x, y), State::Empty))
-                    .collect::<Vec<_>>()
-            })
-            .collect();
-        Self(points)
+impl State {
+    fn parse(c: char) -> Option<Self> {
+        match c {
+            '#' => Some(State::Wall),
+            '.' => Some(State::Empty),
+            '@' => Some(State::Start),
+            c if c.is_ascii_lowercase() => Some(State::Key(c)),
+            c if c.is_ascii_uppercase() => Some(State::Door(c.to_ascii_lowercase())),
+            _ => None,
+        }
     }
 
-    fn contains(&self, point: &Point) -> bool {
-        self.points().contains(point)
+    fn to_char(&self) -> char {
+        match self {
+            State::Wall => '#',
+            State::Empty => '.',
+            State::Start => '@',
+            State::Key(c) => *c,
+            State::Door(c) => c.to_ascii_uppercase(),
+        }
     }
+}
 
-    fn points(&self) -> HashSet<&Point> {
-        self.0.iter().map(|(point, _state)| point).collect()
-    }
+#[derive(Debug)]
+struct Graph(Grid<State>);
 
-    fn max(&self) -> Point {
-        let max_x = self.points().iter().map(|Point(x, _y)| *x).max().unwrap();
-        let max_y = self.points().iter().map(|Point(_x, y)| *y).max().unwrap();
-        Point(max_x, max_y)
+impl Graph {
+    fn new(input: &str) -> Result<Self, ParseError> {
+        Ok(Self(Grid::parse(input, State::parse)?))
     }
 
     fn draw(&self) {
-        let Point(max_x, max_y) = self.max();
-        for y in 0..=(max_y + 1) {
-            for x in 0..=(max_x + 1) {
-                if self.contains(&Point(x, y)) {
-                    print!(".");
-                } else {
-                    print!("#");
-                }
-            }
-            println!("");
-        }
+        println!("{}", self.0.render(State::to_char));
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::INPUT;
 
     #[test]
+    // FIXME: Part 1 is not solved yet, so this draws the map and then hits
+    // the todo!()
+    #[ignore]
     fn test_foo() {
-        let input = include_str!("day18.input");
-        let graph = Graph::new(&input);
+        let input = crate::input(18, INPUT);
+        let graph = Graph::new(input).unwrap();
         graph.draw();
         todo!();
     }
@@ -0,0 +1,268 @@
+// https://adventofcode.com/2019/day/19
+
+use crate::day5;
+use aoc_common::{Answer, ParseError, Point, Solution, SolveError};
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Rows which do not contain the beam are searched up to `MAX_SLOPE * y`
+const MAX_SLOPE: usize = 10;
+
+/// A prober queries the drone system for points in the tractor beam.
+///
+/// The drone program halts after each query, so every query runs a fresh
+/// copy of the program. Results are cached, and the number of program runs
+/// is recorded.
+struct Prober {
+    template: day5::Program,
+    cache: HashMap<Point<usize>, bool>,
+    runs: usize,
+}
+
+impl Prober {
+    fn new(program: Vec<i64>) -> Self {
+        let template = day5::Program::new(program);
+        let cache = HashMap::new();
+        let runs = 0;
+        Prober {
+            template,
+            cache,
+            runs,
+        }
+    }
+
+    /// Returns true if the point is being pulled by the tractor beam. Fails
+    /// if the drone program does not report 0 or 1.
+    fn is_pulled(&mut self, point: Point<usize>) -> Result<bool, SolveError> {
+        if let Some(pulled) = self.cache.get(&point) {
+            return Ok(*pulled);
+        }
+        let mut program = self.template.clone();
+        let outputs = program.run(&[point.0 as i64, point.1 as i64])?;
+        self.runs += 1;
+        let pulled = match outputs.as_slice() {
+            [0] => false,
+            [1] => true,
+            _ => {
+                let message = format!("invalid drone output: {:?}", outputs);
+                return Err(SolveError::Runtime(message));
+            }
+        };
+        self.cache.insert(point, pulled);
+        Ok(pulled)
+    }
+
+    /// Returns the first X value in `xs` on row `y` which is pulled by the
+    /// beam, or which is not if `pulled` is false
+    fn find(
+        &mut self,
+        xs: Range<usize>,
+        y: usize,
+        pulled: bool,
+    ) -> Result<Option<usize>, SolveError> {
+        for x in xs {
+            if self.is_pulled(Point(x, y))? == pulled {
+                return Ok(Some(x));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the number of times the drone program has been run
+    fn runs(&self) -> usize {
+        self.runs
+    }
+
+    /// Returns the beam's span on row `y` as a half-open range of X values.
+    ///
+    /// The beam widens as it moves away from the emitter, so both edges
+    /// only move right from one row to the next. The search starts from the
+    /// previous row's span, and does not look past `max_x`.
+    fn span(
+        &mut self,
+        y: usize,
+        previous: (usize, usize),
+        max_x: usize,
+    ) -> Result<Option<(usize, usize)>, SolveError> {
+        let (left, right) = previous;
+        let left = match self.find(left..max_x, y, true)? {
+            Some(left) => left,
+            None => return Ok(None),
+        };
+        let right = self
+            .find(std::cmp::max(left + 1, right)..max_x, y, false)?
+            .unwrap_or(max_x);
+        Ok(Some((left, right)))
+    }
+}
+
+/// Counts the points pulled by the beam by querying every point in the area
+fn count_affected(prober: &mut Prober, width: usize, height: usize) -> Result<usize, SolveError> {
+    let mut count = 0;
+    for y in 0..height {
+        for x in 0..width {
+            if prober.is_pulled(Point(x, y))? {
+                count += 1;
+            }
+        }
+    }
+    Ok(count)
+}
+
+/// Counts the points pulled by the beam by following the edges of the beam
+fn count_affected_edges(
+    prober: &mut Prober,
+    width: usize,
+    height: usize,
+) -> Result<usize, SolveError> {
+    let mut count = 0;
+    let mut previous = (0, 0);
+    for y in 0..height {
+        if let Some((left, right)) = prober.span(y, previous, width)? {
+            count += right - left;
+            previous = (left, right);
+        }
+    }
+    Ok(count)
+}
+
+/// Returns the top-left point of the closest square of `size` which fits
+/// entirely in the beam.
+///
+/// The bottom-left corner of the square follows the left edge of the beam,
+/// so only the top-right corner needs to be checked on each row.
+fn find_square(prober: &mut Prober, size: usize) -> Result<Point<usize>, SolveError> {
+    assert!(size != 0);
+    let mut left = 0;
+    for y in (size - 1).. {
+        let max_x = std::cmp::max(left, MAX_SLOPE * y) + 1;
+        let x = match prober.find(left..max_x, y, true)? {
+            Some(x) => x,
+            None => continue,
+        };
+        left = x;
+        let top = y + 1 - size;
+        if prober.is_pulled(Point(x + size - 1, top))? {
+            return Ok(Point(x, top));
+        }
+    }
+    unreachable!()
+}
+
+/// The size of the area scanned in part 1
+const AREA_SIZE: usize = 50;
+
+/// The size of Santa's ship
+const SHIP_SIZE: usize = 100;
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        day5::parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        let mut prober = Prober::new(day5::parse_input(input)?);
+        Ok(count_affected_edges(&mut prober, AREA_SIZE, AREA_SIZE)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        let mut prober = Prober::new(day5::parse_input(input)?);
+        let Point(x, y) = find_square(&mut prober, SHIP_SIZE)?;
+        Ok((x * 10000 + y).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The slope of a beam edge, as the ratio of X to Y
+    type Slope = (usize, usize);
+
+    /// Returns a drone program for a beam bounded by the lines
+    /// `x = y * lo.0 / lo.1` and `x = y * hi.0 / hi.1`
+    fn beam_program(lo: Slope, hi: Slope) -> Vec<i64> {
+        let (lo, hi) = ((lo.0 as i64, lo.1 as i64), (hi.0 as i64, hi.1 as i64));
+        vec![
+            3, 100, // x
+            3, 101, // y
+            1002, 101, lo.0, 102, // y * lo.0
+            1002, 100, lo.1, 103, // x * lo.1
+            7, 103, 102, 104, // left of the beam
+            1002, 100, hi.1, 105, // x * hi.1
+            1002, 101, hi.0, 106, // y * hi.0
+            7, 106, 105, 107, // right of the beam
+            1, 104, 107, 108, //
+            108, 0, 108, 109, // neither
+            4, 109, //
+            99,
+        ]
+    }
+
+    fn in_beam(lo: Slope, hi: Slope, Point(x, y): Point<usize>) -> bool {
+        y * lo.0 <= x * lo.1 && x * hi.1 <= y * hi.0
+    }
+
+    const BEAMS: &[(Slope, Slope)] = &[((3, 2), (7, 4)), ((1, 3), (1, 2)), ((7, 5), (3, 2))];
+
+    #[test]
+    fn test_prober() {
+        let mut prober = Prober::new(beam_program((1, 1), (2, 1)));
+        assert_eq!(prober.is_pulled(Point(0, 0)), Ok(true));
+        assert_eq!(prober.is_pulled(Point(3, 2)), Ok(true));
+        assert_eq!(prober.is_pulled(Point(1, 2)), Ok(false));
+        assert_eq!(prober.is_pulled(Point(5, 2)), Ok(false));
+        assert_eq!(prober.runs(), 4);
+        assert_eq!(prober.is_pulled(Point(3, 2)), Ok(true));
+        assert_eq!(prober.runs(), 4);
+
+        // reports the point back instead of whether it is pulled
+        let mut prober = Prober::new(vec![3, 10, 3, 11, 4, 10, 4, 11, 99]);
+        assert_eq!(
+            prober.is_pulled(Point(2, 3)),
+            Err(SolveError::Runtime(
+                "invalid drone output: [2, 3]".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_count_affected() {
+        for (lo, hi) in BEAMS {
+            let program = beam_program(*lo, *hi);
+            let expected = (0..50)
+                .flat_map(|y| (0..50).map(move |x| Point(x, y)))
+                .filter(|point| in_beam(*lo, *hi, *point))
+                .count();
+
+            let mut prober = Prober::new(program.clone());
+            assert_eq!(count_affected(&mut prober, 50, 50), Ok(expected));
+            assert_eq!(prober.runs(), 50 * 50);
+
+            let mut prober = Prober::new(program);
+            assert_eq!(count_affected_edges(&mut prober, 50, 50), Ok(expected));
+            assert!(prober.runs() < 50 * 50 / 2);
+        }
+    }
+
+    #[test]
+    fn test_find_square() {
+        for (lo, hi) in BEAMS {
+            let program = beam_program(*lo, *hi);
+            let size = 10;
+            let fits = |Point(x, y)| {
+                in_beam(*lo, *hi, Point(x, y + size - 1))
+                    && in_beam(*lo, *hi, Point(x + size - 1, y))
+            };
+            let expected = (0..)
+                .flat_map(|y| (0..MAX_SLOPE * (y + size)).map(move |x| Point(x, y)))
+                .find(|point| fits(*point))
+                .unwrap();
+
+            let mut prober = Prober::new(program);
+            assert_eq!(find_square(&mut prober, size), Ok(expected));
+        }
+    }
+}
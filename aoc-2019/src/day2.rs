@@ -1,90 +1,173 @@
 // https://adventofcode.com/2019/day/2
 
+use crate::day5::IntcodeError;
+use aoc_common::{parse_comma_separated, Answer, ParseError, Solution, SolveError};
+
 const OPCODE_ADD: usize = 1;
 const OPCODE_MULT: usize = 2;
 const OPCODE_HALT: usize = 99;
 
-fn run_program(mut input: Vec<usize>) -> Vec<usize> {
+/// Returns the error for an address outside of the program, used by the
+/// instruction at `position`
+fn invalid_address(position: usize, address: usize) -> IntcodeError {
+    let address = address as i64;
+    IntcodeError::Address { position, address }
+}
+
+/// Returns the value at `address`, for the instruction at `position`
+fn read(input: &[usize], position: usize, address: usize) -> Result<usize, IntcodeError> {
+    input
+        .get(address)
+        .cloned()
+        .ok_or_else(|| invalid_address(position, address))
+}
+
+/// Runs the program until it halts. Fails on an invalid opcode, or an
+/// address outside of the program.
+fn run_program(mut input: Vec<usize>) -> Result<Vec<usize>, IntcodeError> {
     let mut i = 0;
     while i < input.len() {
         let opcode = input[i];
-        match opcode {
+        let value = match opcode {
             OPCODE_ADD => {
-                let a = input[i + 1];
-                let b = input[i + 2];
-                let target = input[i + 3];
-                input[target] = input[a] + input[b];
+                let a = read(&input, i, read(&input, i, i + 1)?)?;
+                let b = read(&input, i, read(&input, i, i + 2)?)?;
+                a + b
             }
             OPCODE_MULT => {
-                let a = input[i + 1];
-                let b = input[i + 2];
-                let target = input[i + 3];
-                input[target] = input[a] * input[b];
+                let a = read(&input, i, read(&input, i, i + 1)?)?;
+                let b = read(&input, i, read(&input, i, i + 2)?)?;
+                a * b
             }
             OPCODE_HALT => {
                 break;
             }
-            _ => panic!("invalid opcode: {}", opcode),
-        }
+            _ => {
+                let instruction = opcode as i64;
+                return Err(IntcodeError::Opcode {
+                    position: i,
+                    instruction,
+                });
+            }
+        };
+        let target = read(&input, i, i + 3)?;
+        *input
+            .get_mut(target)
+            .ok_or_else(|| invalid_address(i, target))? = value;
         i += 4;
     }
 
-    input
+    Ok(input)
 }
 
-fn find_verb_noun(mut input: Vec<usize>, output: usize) -> Option<(usize, usize)> {
+fn find_verb_noun(
+    mut input: Vec<usize>,
+    output: usize,
+) -> Result<Option<(usize, usize)>, IntcodeError> {
     for verb in 0..=99 {
         for noun in 0..=99 {
             input[1] = verb;
             input[2] = noun;
-            if run_program(input.clone())[0] == output {
-                return Some((verb, noun));
+            if run_program(input.clone())?[0] == output {
+                return Ok(Some((verb, noun)));
             }
         }
     }
-    None
+    Ok(None)
+}
+
+/// Parses a program, which must be long enough to hold a verb and a noun
+fn parse_input(input: &str) -> Result<Vec<usize>, ParseError> {
+    let program = parse_comma_separated(input)?;
+    if program.len() < 3 {
+        return Err(ParseError::new(
+            "a program of at least 3 values",
+            input.trim(),
+        ));
+    }
+    Ok(program)
+}
+
+/// The output part 2 is looking for
+const TARGET_OUTPUT: usize = 19690720;
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        let mut program = parse_input(input)?;
+        // "replace position 1 with the value 12 and replace position 2 with
+        // the value 2"
+        program[1] = 12;
+        program[2] = 2;
+        Ok(run_program(program)?[0].into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(find_verb_noun(parse_input(input)?, TARGET_OUTPUT)?
+            .map(|(verb, noun)| 100 * verb + noun)
+            .into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    fn parse_input(input: &str) -> Vec<usize> {
-        input
-            .trim_end()
-            .split(",")
-            .map(|s| s.parse::<usize>().unwrap())
-            .collect()
-    }
+    use aoc_common::INPUT;
 
     fn my_input() -> Vec<usize> {
-        let input = include_str!("day2.input");
-        parse_input(input)
+        let input = crate::input(2, INPUT);
+        parse_input(input).unwrap()
     }
 
     #[test]
     fn test_run_program() {
         let tests = &[("1,9,10,3,2,3,11,0,99,30,40,50", 3500), ("1,0,0,0,99", 2)];
         for (input, expected) in tests {
-            let input = parse_input(input);
-            assert_eq!(&run_program(input)[0], expected);
+            let input = parse_input(input).unwrap();
+            assert_eq!(&run_program(input).unwrap()[0], expected);
+        }
+
+        let tests = &[
+            (
+                "1,0,0,0,77,0,0,0",
+                IntcodeError::Opcode {
+                    position: 4,
+                    instruction: 77,
+                },
+            ),
+            ("1,0,0,9", invalid_address(0, 9)),
+            ("2,0,7,0", invalid_address(0, 7)),
+            ("1,0,0", invalid_address(0, 3)),
+        ];
+        for (input, expected) in tests {
+            let input = parse_input(input).unwrap();
+            assert_eq!(run_program(input).as_ref(), Err(expected));
         }
+        assert_eq!(
+            parse_input("1,0\n"),
+            Err(ParseError::new("a program of at least 3 values", "1,0"))
+        );
 
         let mut input = my_input();
         // replace position 1 with the value 12 and replace position 2 with the
         // value 2
         input[1] = 12;
         input[2] = 2;
-        assert_eq!(run_program(input)[0], 3760627);
+        assert_eq!(run_program(input).unwrap()[0], 3760627);
     }
 
     #[test]
     fn test_find_verb_noun() {
         let input = my_input();
-        assert_eq!(find_verb_noun(input, 3760627), Some((12, 2)));
+        assert_eq!(find_verb_noun(input, 3760627), Ok(Some((12, 2))));
 
         let input = my_input();
-        let (verb, noun) = find_verb_noun(input, 19690720).unwrap();
+        let (verb, noun) = find_verb_noun(input, 19690720).unwrap().unwrap();
         assert_eq!(100 * verb + noun, 7195);
     }
 }
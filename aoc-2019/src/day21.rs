@@ -0,0 +1,726 @@
+// https://adventofcode.com/2019/day/21
+
+use crate::day5;
+use aoc_common::{Answer, ParseError, Solution, SolveError};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// The most instructions the springdroid will accept
+const MAX_INSTRUCTIONS: usize = 15;
+
+/// The most literals in a single clause of a generated script
+const MAX_CLAUSE_LEN: u32 = 4;
+
+/// The number of tiles the springdroid moves forward when it jumps
+const JUMP_LEN: usize = 4;
+
+/// A pattern is the sensor readings, with a bit set for each sensor which
+/// detects ground. Sensor A is the lowest bit.
+type Pattern = u16;
+
+/// A register which can be read by a springscript instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Register {
+    /// A ground sensor, where 0 is sensor A
+    Sensor(u8),
+    /// The temporary value register
+    T,
+    /// The jump register
+    J,
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Register::Sensor(n) => write!(f, "{}", (b'A' + n) as char),
+            Register::T => write!(f, "T"),
+            Register::J => write!(f, "J"),
+        }
+    }
+}
+
+impl FromStr for Register {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.as_bytes() {
+            b"T" => Ok(Register::T),
+            b"J" => Ok(Register::J),
+            [c] if (b'A'..=b'I').contains(c) => Ok(Register::Sensor(c - b'A')),
+            _ => Err(format!("invalid register: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    And,
+    Or,
+    Not,
+}
+
+/// A springscript instruction, which stores the result of `op` on `x` and
+/// `y` in `y`
+#[derive(Debug, Clone, PartialEq)]
+struct Instruction {
+    op: Op,
+    x: Register,
+    y: Register,
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self.op {
+            Op::And => "AND",
+            Op::Or => "OR",
+            Op::Not => "NOT",
+        };
+        write!(f, "{} {} {}", op, self.x, self.y)
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<_> = s.split_whitespace().collect();
+        match parts.as_slice() {
+            [op, x, y] => {
+                let op = match *op {
+                    "AND" => Op::And,
+                    "OR" => Op::Or,
+                    "NOT" => Op::Not,
+                    _ => return Err(format!("invalid operation: {}", op)),
+                };
+                let x = x.parse()?;
+                let y = y.parse()?;
+                match y {
+                    Register::T | Register::J => Ok(Instruction { op, x, y }),
+                    Register::Sensor(_) => Err(format!("read-only register: {}", s)),
+                }
+            }
+            _ => Err(format!("invalid instruction: {}", s)),
+        }
+    }
+}
+
+/// Mode is the command which starts the springdroid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// Walk with sensors A through D
+    Walk,
+    /// Run with sensors A through I
+    Run,
+}
+
+impl Mode {
+    /// Returns the number of sensors available
+    fn sensors(self) -> u8 {
+        match self {
+            Mode::Walk => 4,
+            Mode::Run => 9,
+        }
+    }
+}
+
+/// A springscript program
+#[derive(Debug, Clone, PartialEq)]
+struct Script {
+    instructions: Vec<Instruction>,
+    mode: Mode,
+}
+
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for instruction in &self.instructions {
+            writeln!(f, "{}", instruction)?;
+        }
+        match self.mode {
+            Mode::Walk => writeln!(f, "WALK"),
+            Mode::Run => writeln!(f, "RUN"),
+        }
+    }
+}
+
+impl FromStr for Script {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines: Vec<_> = s
+            .lines()
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .collect();
+        let mode = match lines.pop() {
+            Some("WALK") => Mode::Walk,
+            Some("RUN") => Mode::Run,
+            _ => return Err("script does not end with WALK or RUN".to_string()),
+        };
+        let instructions = lines
+            .into_iter()
+            .map(|s| s.parse())
+            .collect::<Result<Vec<Instruction>, _>>()?;
+        Ok(Script { instructions, mode })
+    }
+}
+
+impl Script {
+    /// Returns true if the springdroid jumps with the given sensor readings
+    fn eval(&self, pattern: Pattern) -> bool {
+        let mut t = false;
+        let mut j = false;
+        for Instruction { op, x, y } in &self.instructions {
+            let x = match x {
+                Register::Sensor(n) => pattern & (1 << n) != 0,
+                Register::T => t,
+                Register::J => j,
+            };
+            let y = match y {
+                Register::T => &mut t,
+                Register::J => &mut j,
+                Register::Sensor(_) => panic!("read-only register: {}", y),
+            };
+            *y = match op {
+                Op::And => x && *y,
+                Op::Or => x || *y,
+                Op::Not => !x,
+            };
+        }
+        j
+    }
+}
+
+/// A hull is a row of tiles starting at the springdroid, where true is
+/// ground. Tiles past the end of the hull are ground.
+#[derive(Debug, Clone, PartialEq)]
+struct Hull(Vec<bool>);
+
+impl FromStr for Hull {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tiles = s
+            .chars()
+            .map(|c| match c {
+                '#' => Ok(true),
+                '.' | '@' => Ok(false),
+                _ => Err(format!("invalid hull tile: {}", c)),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Hull(tiles))
+    }
+}
+
+impl Hull {
+    fn is_ground(&self, position: usize) -> bool {
+        self.0.get(position).cloned().unwrap_or(true)
+    }
+
+    /// Returns the sensor readings for the springdroid at `position`
+    fn sensors(&self, position: usize, mode: Mode) -> Pattern {
+        (0..mode.sensors())
+            .filter(|n| self.is_ground(position + 1 + *n as usize))
+            .fold(0, |pattern, n| pattern | (1 << n))
+    }
+
+    /// Moves the springdroid across the hull, returning the position of the
+    /// hole it falls into
+    fn cross(&self, script: &Script) -> Result<(), usize> {
+        let mut position = 0;
+        while position < self.0.len() {
+            if !self.is_ground(position) {
+                return Err(position);
+            }
+            if script.eval(self.sensors(position, script.mode)) {
+                position += JUMP_LEN;
+            } else {
+                position += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses the hull from the frames rendered when the springdroid falls
+fn parse_failure(s: &str) -> Option<Hull> {
+    let (_, frames) = s.split_once("Didn't make it across:")?;
+    let frames: Vec<Vec<&str>> = frames
+        .split("\n\n")
+        .map(|frame| frame.lines().filter(|line| !line.is_empty()).collect())
+        .filter(|frame: &Vec<&str>| !frame.is_empty())
+        .collect();
+    let start = frames.first()?.iter().find_map(|line| line.find('@'))?;
+    let hulls: Vec<&str> = frames
+        .iter()
+        .flat_map(|frame| frame.last())
+        .cloned()
+        .collect();
+    let len = hulls.iter().map(|hull| hull.len()).max()?;
+    let tiles = (start..len)
+        .map(|i| {
+            hulls
+                .iter()
+                .any(|hull| hull.as_bytes().get(i) == Some(&b'#'))
+        })
+        .collect();
+    Some(Hull(tiles))
+}
+
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    /// The springdroid made it across, and reported the amount of hull damage
+    Damage(i64),
+    /// The springdroid fell into a hole on this hull
+    Fell(Hull),
+}
+
+/// Runs the springdroid program with a springscript. Fails if the program
+/// fails, or reports neither the hull damage nor the hull it fell on.
+fn run_script(program: &[i64], script: &Script) -> Result<Outcome, SolveError> {
+    let mut program = day5::Program::new(program.to_vec());
+    let (text, values) = program.run_ascii(&script.to_string())?;
+    match values.last() {
+        Some(damage) => Ok(Outcome::Damage(*damage)),
+        None => parse_failure(&text).map(Outcome::Fell).ok_or_else(|| {
+            SolveError::Runtime(format!("invalid springdroid output: {:?}", text.trim()))
+        }),
+    }
+}
+
+/// A clause is a disjunction of sensor readings. A sensor in `positive`
+/// must detect ground, and a sensor in `negative` must detect a hole.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Clause {
+    positive: Pattern,
+    negative: Pattern,
+}
+
+impl Clause {
+    fn eval(self, pattern: Pattern) -> bool {
+        pattern & self.positive != 0 || !pattern & self.negative != 0
+    }
+
+    /// Returns every clause with up to `MAX_CLAUSE_LEN` sensors
+    fn all(mode: Mode) -> Vec<Clause> {
+        let limit: Pattern = 1 << mode.sensors();
+        let mut clauses = Vec::new();
+        for positive in 0..limit {
+            for negative in 0..limit {
+                let len = (positive | negative).count_ones();
+                if positive & negative == 0 && len != 0 && len <= MAX_CLAUSE_LEN {
+                    clauses.push(Clause { positive, negative });
+                }
+            }
+        }
+        clauses
+    }
+
+    /// Returns true if the clause is a single sensor which must detect ground
+    fn is_single_positive(self) -> bool {
+        self.negative == 0 && self.positive.count_ones() == 1
+    }
+
+    /// Returns the number of instructions the clause adds to a script, when
+    /// it is not the first clause
+    fn cost(self) -> usize {
+        if self.is_single_positive() {
+            1
+        } else {
+            self.compile(Register::T, false).len() + 1
+        }
+    }
+
+    /// Generates the instructions to store the clause in `register`. If the
+    /// register is `fresh`, it has not been written to, and is still false.
+    fn compile(self, register: Register, fresh: bool) -> Vec<Instruction> {
+        let sensors = |pattern: Pattern| {
+            (0..16)
+                .filter(move |n| pattern & (1 << n) != 0)
+                .map(Register::Sensor)
+        };
+        let op = |op, x| Instruction { op, x, y: register };
+        let load = |x| {
+            if fresh {
+                vec![op(Op::Or, x)]
+            } else {
+                vec![op(Op::Not, x), op(Op::Not, register)]
+            }
+        };
+        let mut positive = sensors(self.positive);
+        let mut negative = sensors(self.negative);
+        let mut instructions = Vec::new();
+        match self.negative.count_ones() {
+            0 => instructions.extend(load(positive.next().unwrap())),
+            1 => instructions.push(op(Op::Not, negative.next().unwrap())),
+            _ => {
+                // ¬A ∨ ¬B is ¬(A ∧ B)
+                instructions.extend(load(negative.next().unwrap()));
+                instructions.extend(negative.map(|x| op(Op::And, x)));
+                instructions.push(op(Op::Not, register));
+            }
+        }
+        instructions.extend(positive.map(|x| op(Op::Or, x)));
+        instructions
+    }
+}
+
+/// Generates a script for a conjunction of clauses. The first clause is
+/// stored in J, and each other clause is stored in T, then combined with J.
+fn compile(clauses: &[Clause], mode: Mode) -> Script {
+    // A single sensor can be combined with J directly, so it does not need
+    // to go first
+    let mut clauses = clauses.to_vec();
+    clauses.sort_by_key(|clause| clause.is_single_positive());
+
+    let mut instructions = Vec::new();
+    match clauses.split_first() {
+        Some((first, rest)) => {
+            instructions.extend(first.compile(Register::J, true));
+            let mut fresh = true;
+            for clause in rest {
+                if clause.is_single_positive() {
+                    instructions.push(Instruction {
+                        op: Op::And,
+                        x: Register::Sensor(clause.positive.trailing_zeros() as u8),
+                        y: Register::J,
+                    });
+                    continue;
+                }
+                instructions.extend(clause.compile(Register::T, fresh));
+                instructions.push(Instruction {
+                    op: Op::And,
+                    x: Register::T,
+                    y: Register::J,
+                });
+                fresh = false;
+            }
+        }
+        None => instructions.push(Instruction {
+            op: Op::Not,
+            x: Register::T,
+            y: Register::J,
+        }),
+    }
+    Script { instructions, mode }
+}
+
+/// Returns the number of instructions `compile` generates
+fn compile_cost(clauses: &[Clause]) -> usize {
+    compile(clauses, Mode::Walk).instructions.len()
+}
+
+/// Chooses clauses from `candidates` so that each walk pattern is false for
+/// at least one of them, with at most `budget` instructions.
+///
+/// Each candidate is paired with the set of walk patterns it is false for.
+fn cover(
+    uncovered: u128,
+    candidates: &[(Clause, u128)],
+    budget: usize,
+    chosen: &mut Vec<Clause>,
+) -> bool {
+    if uncovered == 0 {
+        return compile_cost(chosen) <= budget;
+    }
+    let walk = uncovered & uncovered.wrapping_neg();
+    for (clause, covers) in candidates.iter().filter(|(_, covers)| covers & walk != 0) {
+        chosen.push(*clause);
+        if compile_cost(chosen) <= budget && cover(uncovered & !covers, candidates, budget, chosen)
+        {
+            return true;
+        }
+        chosen.pop();
+    }
+    false
+}
+
+/// Finds the shortest script which jumps on the jump patterns, and walks on
+/// the walk patterns
+fn synthesize(jumps: &[Pattern], walks: &[Pattern], mode: Mode) -> Option<Script> {
+    if walks.len() > 128 {
+        return None;
+    }
+    // Only keep the cheapest clause for each set of walk patterns
+    let mut candidates: HashMap<u128, Clause> = HashMap::new();
+    for clause in Clause::all(mode) {
+        if !jumps.iter().all(|pattern| clause.eval(*pattern)) {
+            continue;
+        }
+        let covers = walks
+            .iter()
+            .enumerate()
+            .filter(|(_, pattern)| !clause.eval(**pattern))
+            .fold(0, |covers, (i, _)| covers | (1 << i));
+        if covers == 0 {
+            continue;
+        }
+        let entry = candidates.entry(covers).or_insert(clause);
+        if clause.cost() < entry.cost() {
+            *entry = clause;
+        }
+    }
+    // Drop clauses which are no better than another clause
+    let dominated = |(covers, clause): (&u128, &Clause)| {
+        candidates.iter().any(|(other_covers, other)| {
+            other_covers != covers
+                && covers & other_covers == *covers
+                && other.cost() <= clause.cost()
+        })
+    };
+    let mut candidates: Vec<(Clause, u128)> = candidates
+        .iter()
+        .filter(|candidate| !dominated(*candidate))
+        .map(|(covers, clause)| (*clause, *covers))
+        .collect();
+    candidates.sort_by_key(|(clause, covers)| {
+        let Clause { positive, negative } = *clause;
+        (
+            clause.cost(),
+            std::cmp::Reverse(covers.count_ones()),
+            positive,
+            negative,
+        )
+    });
+
+    let uncovered = (0..walks.len()).fold(0, |uncovered, i| uncovered | (1 << i));
+    let mut chosen = Vec::new();
+    (1..=MAX_INSTRUCTIONS)
+        .find(|budget| cover(uncovered, &candidates, *budget, &mut chosen))
+        .map(|_| compile(&chosen, mode))
+}
+
+/// Decides whether to jump for each pattern the springdroid sees while
+/// crossing the hulls, and returns the first set of decisions that a script
+/// can be generated for
+fn search(
+    hulls: &[Hull],
+    position: usize,
+    mode: Mode,
+    table: &mut HashMap<Pattern, bool>,
+) -> Option<Script> {
+    let hull = match hulls.first() {
+        Some(hull) => hull,
+        None => {
+            let mut table: Vec<_> = table
+                .iter()
+                .map(|(pattern, jump)| (*pattern, *jump))
+                .collect();
+            table.sort();
+            let (jumps, walks): (Vec<_>, Vec<_>) = table.into_iter().partition(|(_, jump)| *jump);
+            let jumps: Vec<_> = jumps.into_iter().map(|(pattern, _)| pattern).collect();
+            let walks: Vec<_> = walks.into_iter().map(|(pattern, _)| pattern).collect();
+            return synthesize(&jumps, &walks, mode);
+        }
+    };
+    if position >= hull.0.len() {
+        return search(&hulls[1..], 0, mode, table);
+    }
+    if !hull.is_ground(position) {
+        return None;
+    }
+    let pattern = hull.sensors(position, mode);
+    if let Some(jump) = table.get(&pattern) {
+        let position = position + if *jump { JUMP_LEN } else { 1 };
+        return search(hulls, position, mode, table);
+    }
+    for (jump, step) in &[(false, 1), (true, JUMP_LEN)] {
+        table.insert(pattern, *jump);
+        if let Some(script) = search(hulls, position + step, mode, table) {
+            return Some(script);
+        }
+    }
+    table.remove(&pattern);
+    None
+}
+
+/// Finds a script which gets the springdroid across all of the hulls
+fn find_script(hulls: &[Hull], mode: Mode) -> Option<Script> {
+    search(hulls, 0, mode, &mut HashMap::new())
+}
+
+/// Finds a script for the springdroid program, learning from each hull the
+/// springdroid falls into. Returns the script and the hull damage.
+fn solve(program: &[i64], mode: Mode) -> Result<Option<(Script, i64)>, SolveError> {
+    let mut hulls = Vec::new();
+    loop {
+        let script = match find_script(&hulls, mode) {
+            Some(script) => script,
+            None => return Ok(None),
+        };
+        match run_script(program, &script)? {
+            Outcome::Damage(damage) => return Ok(Some((script, damage))),
+            Outcome::Fell(hull) => {
+                if hulls.contains(&hull) {
+                    return Ok(None);
+                }
+                hulls.push(hull);
+            }
+        }
+    }
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        day5::parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(solve(&day5::parse_input(input)?, Mode::Walk)?
+            .map(|(_, damage)| damage)
+            .into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(solve(&day5::parse_input(input)?, Mode::Run)?
+            .map(|(_, damage)| damage)
+            .into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: &str = "NOT A J
+NOT B T
+OR T J
+NOT C T
+OR T J
+AND D J
+WALK
+";
+
+    const FAILURE: &str = "Input instructions:
+
+Walking...
+
+
+Didn't make it across:
+
+.................
+.................
+@................
+#####...#########
+
+.................
+.................
+.@...............
+#####...#########
+
+.................
+..@..............
+.................
+#####...#########
+
+...@.............
+.................
+.................
+#####...#########
+
+.................
+....@............
+.................
+#####...#########
+
+.................
+.................
+.....@...........
+#####...#########
+
+.................
+.................
+.................
+#####.@.#########
+
+";
+
+    const WALK_HULLS: &[&str] = &[
+        "#####.###########",
+        "#####..#.########",
+        "#####...#########",
+    ];
+
+    const RUN_HULLS: &[&str] = &[
+        "#####.###########",
+        "#####..#.########",
+        "#####...#########",
+        "#####.#.##..#####",
+        "#####.#..########",
+        "#####.##.##.#.###",
+        "#####.##.#.##.###",
+        "#####.#.#.##.####",
+    ];
+
+    fn parse_hulls(hulls: &[&str]) -> Vec<Hull> {
+        hulls.iter().map(|hull| hull.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_parse_script() {
+        let script: Script = SCRIPT.parse().unwrap();
+        assert_eq!(script.instructions.len(), 6);
+        assert_eq!(script.mode, Mode::Walk);
+        assert_eq!(script.to_string(), SCRIPT);
+
+        assert!("NOT A B\nWALK".parse::<Script>().is_err());
+        assert!("NOT A J".parse::<Script>().is_err());
+        assert!("XOR A J\nRUN".parse::<Script>().is_err());
+    }
+
+    #[test]
+    fn test_eval() {
+        let script: Script = SCRIPT.parse().unwrap();
+        assert!(!script.eval(0b1111));
+        assert!(script.eval(0b1011));
+        assert!(!script.eval(0b0011));
+
+        let hulls = parse_hulls(WALK_HULLS);
+        for hull in &hulls {
+            assert_eq!(hull.cross(&script), Ok(()));
+        }
+        let hull: Hull = "#####.#..########".parse().unwrap();
+        assert_eq!(hull.cross(&script), Ok(()));
+        let hull: Hull = "#####.#.##..#####".parse().unwrap();
+        assert_eq!(hull.cross(&script), Err(7));
+    }
+
+    #[test]
+    fn test_parse_failure() {
+        let hull = parse_failure(FAILURE).unwrap();
+        assert_eq!(hull, "#####...#########".parse().unwrap());
+        assert_eq!(parse_failure("Input instructions:\n"), None);
+    }
+
+    #[test]
+    fn test_synthesize() {
+        // jump if C is a hole and D is ground
+        let script = synthesize(&[0b1011, 0b1001], &[0b1111, 0b0011, 0b0110], Mode::Walk).unwrap();
+        assert_eq!(script.to_string(), "NOT C J\nAND D J\nWALK\n");
+
+        let script = synthesize(&[], &[], Mode::Run).unwrap();
+        assert_eq!(script.to_string(), "NOT T J\nRUN\n");
+        assert!(synthesize(&[0b1011], &[0b1011], Mode::Walk).is_none());
+    }
+
+    #[test]
+    fn test_find_script() {
+        let tests = &[(WALK_HULLS, Mode::Walk), (RUN_HULLS, Mode::Run)];
+        for (hulls, mode) in tests {
+            let hulls = parse_hulls(hulls);
+            let script = find_script(&hulls, *mode).unwrap();
+            assert!(script.instructions.len() <= MAX_INSTRUCTIONS);
+            assert_eq!(script.mode, *mode);
+            for hull in &hulls {
+                assert_eq!(hull.cross(&script), Ok(()));
+            }
+        }
+
+        // the springdroid can not see far enough ahead to cross both
+        let hulls = parse_hulls(&["#####.#..########", "#####.#.##..#####"]);
+        assert_eq!(find_script(&hulls, Mode::Walk), None);
+        assert!(find_script(&hulls, Mode::Run).is_some());
+    }
+}
@@ -0,0 +1,299 @@
+// https://adventofcode.com/2019/day/23
+
+use crate::day5;
+use aoc_common::{Answer, ParseError, Solution, SolveError};
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+
+/// The address of the NAT
+const NAT_ADDRESS: usize = 255;
+
+/// The value a computer receives when it has no packets waiting
+const NO_PACKET: i64 = -1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Packet {
+    dest: usize,
+    x: i64,
+    y: i64,
+}
+
+/// A network of computers running the same program, with one input queue
+/// per address.
+///
+/// Computers are run in address order, one at a time, until each one is
+/// waiting for input. This makes the order packets are delivered in
+/// reproducible.
+struct Network {
+    computers: Vec<day5::Program>,
+    queues: Vec<VecDeque<i64>>,
+    /// Output which does not make up a full packet yet
+    buffers: Vec<Vec<i64>>,
+    /// The last packet sent to the NAT
+    nat: Option<Packet>,
+}
+
+impl Network {
+    /// Creates `size` computers. Each computer boots with its network
+    /// address the first time it runs.
+    fn new(program: &[i64], size: usize) -> Self {
+        let computers = vec![day5::Program::new(program.to_vec()); size];
+        let queues = (0..size)
+            .map(|address| vec![address as i64].into())
+            .collect();
+        let buffers = vec![Vec::new(); size];
+        let nat = None;
+        Network {
+            computers,
+            queues,
+            buffers,
+            nat,
+        }
+    }
+
+    /// Queues a packet for delivery. Fails if no computer has the packet's
+    /// address.
+    fn send(&mut self, packet: Packet) -> Result<(), SolveError> {
+        if packet.dest == NAT_ADDRESS {
+            self.nat = Some(packet);
+        } else {
+            let queue = self
+                .queues
+                .get_mut(packet.dest)
+                .ok_or_else(|| unknown_address(packet.dest as i64))?;
+            queue.push_back(packet.x);
+            queue.push_back(packet.y);
+        }
+        Ok(())
+    }
+
+    /// Runs each computer in address order, and returns the packets which
+    /// were sent
+    fn step(&mut self) -> Result<Vec<Packet>, SolveError> {
+        let mut sent = Vec::new();
+        for address in 0..self.computers.len() {
+            let inputs: Vec<i64> = if self.queues[address].is_empty() {
+                vec![NO_PACKET]
+            } else {
+                self.queues[address].drain(..).collect()
+            };
+            let outputs = self.computers[address].run(&inputs)?;
+            let buffer = &mut self.buffers[address];
+            buffer.extend(outputs);
+            let len = buffer.len() - buffer.len() % 3;
+            let packets = buffer
+                .drain(..len)
+                .collect::<Vec<_>>()
+                .chunks(3)
+                .map(|packet| {
+                    let dest =
+                        usize::try_from(packet[0]).map_err(|_| unknown_address(packet[0]))?;
+                    let (x, y) = (packet[1], packet[2]);
+                    Ok(Packet { dest, x, y })
+                })
+                .collect::<Result<Vec<_>, SolveError>>()?;
+            for packet in packets {
+                self.send(packet)?;
+                sent.push(packet);
+            }
+        }
+        Ok(sent)
+    }
+
+    /// Returns true if no packets are waiting to be delivered
+    fn is_idle(&self) -> bool {
+        self.queues.iter().all(|queue| queue.is_empty())
+            && self.buffers.iter().all(|buffer| buffer.is_empty())
+    }
+
+    /// Returns true if every computer has halted. Fails if a computer
+    /// halted part way through sending a packet.
+    fn is_halted(&self) -> Result<bool, SolveError> {
+        if !self.computers.iter().all(|computer| computer.complete) {
+            return Ok(false);
+        }
+        match self.buffers.iter().position(|buffer| !buffer.is_empty()) {
+            Some(address) => Err(SolveError::Runtime(format!(
+                "computer {} halted part way through a packet: {:?}",
+                address, self.buffers[address]
+            ))),
+            None => Ok(true),
+        }
+    }
+
+    /// Returns the first packet sent to the NAT, or `None` if every
+    /// computer halts first
+    fn first_nat_packet(&mut self) -> Result<Option<Packet>, SolveError> {
+        loop {
+            let sent = self.step()?;
+            if let Some(packet) = sent.into_iter().find(|packet| packet.dest == NAT_ADDRESS) {
+                return Ok(Some(packet));
+            }
+            if self.is_halted()? {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Returns the first Y value the NAT delivers to address 0 twice in a
+    /// row.
+    ///
+    /// The network is idle once a round passes where no packets are sent,
+    /// and every computer is waiting for a packet. The NAT then delivers the
+    /// last packet it received to address 0.
+    fn first_repeated_nat_y(&mut self) -> Result<Option<i64>, SolveError> {
+        let mut last_y = None;
+        loop {
+            let sent = self.step()?;
+            if self.is_halted()? {
+                return Ok(None);
+            }
+            if !sent.is_empty() || !self.is_idle() {
+                continue;
+            }
+            let Packet { x, y, .. } = match self.nat {
+                Some(packet) => packet,
+                None => return Ok(None),
+            };
+            if last_y == Some(y) {
+                return Ok(Some(y));
+            }
+            last_y = Some(y);
+            self.send(Packet { dest: 0, x, y })?;
+        }
+    }
+}
+
+/// Returns the error for a packet sent to an address with no computer
+fn unknown_address(address: i64) -> SolveError {
+    SolveError::Runtime(format!("packet sent to unknown address {}", address))
+}
+
+/// The number of computers on the network
+const NETWORK_SIZE: usize = 50;
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        day5::parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        let mut network = Network::new(&day5::parse_input(input)?, NETWORK_SIZE);
+        Ok(network.first_nat_packet()?.map(|packet| packet.y).into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        let mut network = Network::new(&day5::parse_input(input)?, NETWORK_SIZE);
+        Ok(network.first_repeated_nat_y()?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Each computer sends (A, A + 1) to the NAT when it boots. When it
+    /// receives (X, Y), it sends (X - 1, Y + X) to the NAT, or (0, Y) once X
+    /// is 0.
+    const PROGRAM: &str = "3,1000,104,255,4,1000,1001,1000,1,1003,4,1003,\
+        3,1001,1008,1001,-1,1003,1005,1003,12,3,1002,1006,1001,43,\
+        104,255,101,-1,1001,1003,4,1003,1,1002,1001,1003,4,1003,1105,1,12,\
+        104,255,104,0,4,1002,1105,1,12";
+
+    #[test]
+    fn test_send() {
+        let program = day5::parse_input(PROGRAM).unwrap();
+        let mut network = Network::new(&program, 3);
+        assert!(!network.is_idle());
+        network
+            .send(Packet {
+                dest: 1,
+                x: 7,
+                y: 8,
+            })
+            .unwrap();
+        assert_eq!(network.queues[1], &[1, 7, 8]);
+        network
+            .send(Packet {
+                dest: 255,
+                x: 9,
+                y: 10,
+            })
+            .unwrap();
+        assert_eq!(
+            network.nat,
+            Some(Packet {
+                dest: 255,
+                x: 9,
+                y: 10
+            })
+        );
+        assert_eq!(
+            network.send(Packet {
+                dest: 3,
+                x: 1,
+                y: 2,
+            }),
+            Err(SolveError::Runtime(
+                "packet sent to unknown address 3".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_first_nat_packet() {
+        let program = day5::parse_input(PROGRAM).unwrap();
+        let mut network = Network::new(&program, 50);
+        let packet = network.first_nat_packet();
+        assert_eq!(
+            packet,
+            Ok(Some(Packet {
+                dest: 255,
+                x: 0,
+                y: 1
+            }))
+        );
+    }
+
+    #[test]
+    fn test_first_nat_packet_halted() {
+        let tests = &[
+            // Waits for a second input before sending
+            ("3,100,3,101,104,255,104,1,104,2,99", Ok(Some(1))),
+            ("99", Ok(None)),
+            (
+                "104,255,104,1,99",
+                Err(SolveError::Runtime(
+                    "computer 0 halted part way through a packet: [255, 1]".to_string(),
+                )),
+            ),
+            (
+                "104,-1,104,1,104,2,99",
+                Err(SolveError::Runtime(
+                    "packet sent to unknown address -1".to_string(),
+                )),
+            ),
+        ];
+        for (program, expected) in tests {
+            let program = day5::parse_input(program).unwrap();
+            let mut network = Network::new(&program, 3);
+            let packet = network.first_nat_packet();
+            assert_eq!(
+                packet.map(|packet| packet.map(|packet| packet.x)),
+                *expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_first_repeated_nat_y() {
+        let program = day5::parse_input(PROGRAM).unwrap();
+        let tests = &[(3, 6), (50, 1275)];
+        for (size, expected) in tests {
+            let mut network = Network::new(&program, *size);
+            assert_eq!(network.first_repeated_nat_y(), Ok(Some(*expected)));
+        }
+    }
+}
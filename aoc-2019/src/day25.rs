@@ -0,0 +1,601 @@
+// https://adventofcode.com/2019/day/25
+
+use crate::day5;
+use aoc_common::{Answer, ParseError, Solution, SolveError};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// The most instructions the droid program may run for a single command.
+/// Some items make the program loop forever.
+const MAX_STEPS: usize = 10_000_000;
+
+/// The room which leads to the pressure-sensitive floor
+const CHECKPOINT: &str = "Security Checkpoint";
+
+/// The room which weighs the droid
+const FLOOR: &str = "Pressure-Sensitive Floor";
+
+/// A text adventure which takes one command at a time
+trait Game: Clone {
+    /// Sends a command, and returns the text the game prints. Returns None
+    /// if the game stops responding, and fails if the game itself fails.
+    fn send(&mut self, command: &str) -> Result<Option<String>, SolveError>;
+
+    /// Returns true if the game has ended
+    fn is_over(&self) -> bool;
+}
+
+/// The droid program, played through its ASCII interface
+#[derive(Debug, Clone)]
+struct Droid(day5::Program);
+
+impl Droid {
+    fn new(program: Vec<i64>) -> Self {
+        Droid(day5::Program::new(program))
+    }
+}
+
+impl Game for Droid {
+    /// Runs the program until it waits for the next command. A program which
+    /// runs out of steps has stopped responding.
+    fn send(&mut self, command: &str) -> Result<Option<String>, SolveError> {
+        let input = if command.is_empty() {
+            String::new()
+        } else {
+            format!("{}\n", command)
+        };
+        match self.0.run_for(&day5::ascii_input(&input), MAX_STEPS) {
+            Ok(outputs) => Ok(Some(day5::ascii_output(&outputs).0)),
+            Err(day5::IntcodeError::Timeout { .. }) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn is_over(&self) -> bool {
+        self.0.complete
+    }
+}
+
+/// Returns the direction which leads back through a door. Fails if the game
+/// listed a door in an unknown direction.
+fn opposite(direction: &str) -> Result<&'static str, SolveError> {
+    match direction {
+        "north" => Ok("south"),
+        "south" => Ok("north"),
+        "east" => Ok("west"),
+        "west" => Ok("east"),
+        _ => Err(SolveError::Runtime(format!(
+            "unknown door direction: {:?}",
+            direction
+        ))),
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Room {
+    name: String,
+    doors: Vec<String>,
+    items: Vec<String>,
+}
+
+/// Parses each room described in the game's output
+fn parse_rooms(s: &str) -> Vec<Room> {
+    enum Section {
+        None,
+        Doors,
+        Items,
+    }
+
+    let mut rooms: Vec<Room> = Vec::new();
+    let mut section = Section::None;
+    for line in s.lines() {
+        if let Some(name) = line.strip_prefix("== ").and_then(|s| s.strip_suffix(" ==")) {
+            let name = name.to_string();
+            rooms.push(Room {
+                name,
+                ..Room::default()
+            });
+            section = Section::None;
+        } else if line == "Doors here lead:" {
+            section = Section::Doors;
+        } else if line == "Items here:" {
+            section = Section::Items;
+        } else if let (Some(entry), Some(room)) = (line.strip_prefix("- "), rooms.last_mut()) {
+            match section {
+                Section::Doors => room.doors.push(entry.to_string()),
+                Section::Items => room.items.push(entry.to_string()),
+                Section::None => {}
+            }
+        } else {
+            section = Section::None;
+        }
+    }
+    rooms
+}
+
+/// Parses the room the droid is in after a command, which is the last room
+/// described
+fn parse_room(s: &str) -> Result<Room, SolveError> {
+    parse_rooms(s)
+        .pop()
+        .ok_or_else(|| SolveError::Runtime(format!("expected a room, found {:?}", s.trim())))
+}
+
+/// Parses the airlock password from the game's output
+fn parse_password(s: &str) -> Option<String> {
+    let (_, rest) = s.split_once("typing ")?;
+    let password: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    if password.is_empty() {
+        None
+    } else {
+        Some(password)
+    }
+}
+
+/// An explorer maps the ship, and picks up every item which is safe to
+/// carry
+struct Explorer<G: Game> {
+    game: G,
+    rooms: HashMap<String, Room>,
+    /// The room each door leads to, keyed by room and direction
+    doors: HashMap<(String, String), String>,
+    /// The current room
+    room: String,
+    inventory: Vec<String>,
+    /// Items which end the game, or leave the droid stuck
+    blacklist: HashSet<String>,
+    /// The direction of the pressure-sensitive floor from the checkpoint
+    floor: Option<String>,
+}
+
+impl<G: Game> Explorer<G> {
+    fn new(game: G) -> Result<Self, SolveError> {
+        let mut explorer = Explorer {
+            game,
+            rooms: HashMap::new(),
+            doors: HashMap::new(),
+            room: String::new(),
+            inventory: Vec::new(),
+            blacklist: HashSet::new(),
+            floor: None,
+        };
+        let output = explorer.send("")?;
+        let room = parse_room(&output)?;
+        explorer.enter(room);
+        Ok(explorer)
+    }
+
+    /// Sends a command to the game. Fails if the game stops responding.
+    fn send(&mut self, command: &str) -> Result<String, SolveError> {
+        self.game.send(command)?.ok_or_else(|| {
+            SolveError::Runtime(format!("the game stopped responding to {:?}", command))
+        })
+    }
+
+    fn enter(&mut self, room: Room) {
+        self.room = room.name.clone();
+        self.rooms.insert(room.name.clone(), room);
+    }
+
+    /// Moves through a door. If the droid is ejected from the
+    /// pressure-sensitive floor, it ends up back in the checkpoint.
+    fn go(&mut self, direction: &str) -> Result<(), SolveError> {
+        let output = self.send(direction)?;
+        let room = parse_room(&output)?;
+        if parse_rooms(&output).iter().any(|room| room.name == FLOOR) {
+            self.floor = Some(direction.to_string());
+        } else {
+            let from = self.room.clone();
+            self.doors
+                .insert((from.clone(), direction.to_string()), room.name.clone());
+            self.doors
+                .insert((room.name.clone(), opposite(direction)?.to_string()), from);
+        }
+        self.enter(room);
+        Ok(())
+    }
+
+    /// Returns true if taking the item ends the game, or stops the droid from
+    /// moving. The item is tried on a copy of the game.
+    fn is_deadly(&self, item: &str) -> Result<bool, SolveError> {
+        let mut game = self.game.clone();
+        if game.send(&format!("take {}", item))?.is_none() || game.is_over() {
+            return Ok(true);
+        }
+        let direction = &self.rooms[&self.room].doors[0];
+        Ok(match game.send(direction)? {
+            Some(output) => game.is_over() || parse_rooms(&output).is_empty(),
+            None => true,
+        })
+    }
+
+    /// Takes every item in the current room which is not deadly
+    fn take_items(&mut self) -> Result<(), SolveError> {
+        let items = self.rooms[&self.room].items.clone();
+        for item in items {
+            if self.blacklist.contains(&item) {
+                continue;
+            }
+            if self.is_deadly(&item)? {
+                self.blacklist.insert(item);
+                continue;
+            }
+            self.send(&format!("take {}", item))?;
+            if let Some(room) = self.rooms.get_mut(&self.room) {
+                room.items.retain(|i| i != &item);
+            }
+            self.inventory.push(item);
+        }
+        Ok(())
+    }
+
+    /// Visits every room reachable from the current room, and returns to it
+    fn explore(&mut self) -> Result<(), SolveError> {
+        self.take_items()?;
+        let here = self.room.clone();
+        let doors = self.rooms[&here].doors.clone();
+        for door in doors {
+            let known = self.doors.contains_key(&(here.clone(), door.clone()));
+            if known || self.floor.as_ref() == Some(&door) && here == CHECKPOINT {
+                continue;
+            }
+            let visited = self.rooms.len();
+            self.go(&door)?;
+            if self.room == here {
+                // ejected by the pressure-sensitive floor
+                continue;
+            }
+            if self.rooms.len() > visited {
+                self.explore()?;
+            }
+            self.go(opposite(&door)?)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the directions to get from the current room to `target`
+    fn path_to(&self, target: &str) -> Option<Vec<String>> {
+        let mut previous: HashMap<&str, (&str, &str)> = HashMap::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(self.room.as_str());
+        while let Some(room) = queue.pop_front() {
+            if room == target {
+                let mut path = Vec::new();
+                let mut room = room;
+                while let Some((from, direction)) = previous.get(room) {
+                    path.push(direction.to_string());
+                    room = from;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for ((from, direction), to) in &self.doors {
+                if from == room && to != &self.room && !previous.contains_key(to.as_str()) {
+                    previous.insert(to, (from, direction));
+                    queue.push_back(to);
+                }
+            }
+        }
+        None
+    }
+
+    /// Tries every combination of items on the pressure-sensitive floor, and
+    /// returns the airlock password. Returns None if the checkpoint or the
+    /// floor was not found, or no combination is the right weight.
+    fn crack(&mut self) -> Result<Option<String>, SolveError> {
+        let (path, floor) = match (self.path_to(CHECKPOINT), self.floor.clone()) {
+            (Some(path), Some(floor)) => (path, floor),
+            _ => return Ok(None),
+        };
+        for direction in path {
+            self.go(&direction)?;
+        }
+        let items = std::mem::take(&mut self.inventory);
+        for item in &items {
+            self.send(&format!("drop {}", item))?;
+        }
+        // Gray code order only changes one item between attempts
+        let mut held = vec![false; items.len()];
+        for i in 0..(1_usize << items.len()) {
+            if i != 0 {
+                let n = i.trailing_zeros() as usize;
+                held[n] = !held[n];
+                let verb = if held[n] { "take" } else { "drop" };
+                self.send(&format!("{} {}", verb, items[n]))?;
+            }
+            let output = self.send(&floor)?;
+            if let Some(password) = parse_password(&output) {
+                self.inventory = items
+                    .into_iter()
+                    .zip(held)
+                    .filter(|(_, held)| *held)
+                    .map(|(item, _)| item)
+                    .collect();
+                return Ok(Some(password));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Explores the ship and returns the password for the main airlock
+fn play<G: Game>(game: G) -> Result<Option<String>, SolveError> {
+    let mut explorer = Explorer::new(game)?;
+    explorer.explore()?;
+    explorer.crack()
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        day5::parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(play(Droid::new(day5::parse_input(input)?))?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The doors and items in a room
+    type Contents = (Vec<(&'static str, &'static str)>, Vec<&'static str>);
+
+    /// A small ship which plays like the droid program
+    #[derive(Clone)]
+    struct Ship {
+        rooms: HashMap<&'static str, Contents>,
+        room: &'static str,
+        inventory: Vec<&'static str>,
+        stuck: bool,
+        looping: bool,
+        over: bool,
+    }
+
+    const TARGET_WEIGHT: usize = 13;
+
+    fn weight(item: &str) -> usize {
+        match item {
+            "mug" => 1,
+            "hologram" => 2,
+            "weather machine" => 4,
+            "dark matter" => 8,
+            _ => 100,
+        }
+    }
+
+    impl Ship {
+        fn new() -> Self {
+            let rooms = vec![
+                (
+                    "Hull Breach",
+                    vec![("north", "Kitchen"), ("east", "Stables")],
+                    vec![],
+                ),
+                (
+                    "Kitchen",
+                    vec![
+                        ("south", "Hull Breach"),
+                        ("north", "Arcade"),
+                        ("west", "Observatory"),
+                    ],
+                    vec!["mug"],
+                ),
+                (
+                    "Arcade",
+                    vec![("south", "Kitchen")],
+                    vec!["molten lava", "infinite loop"],
+                ),
+                (
+                    "Observatory",
+                    vec![("east", "Kitchen")],
+                    vec!["weather machine", "dark matter"],
+                ),
+                (
+                    "Stables",
+                    vec![("west", "Hull Breach"), ("east", CHECKPOINT)],
+                    vec!["giant electromagnet", "hologram"],
+                ),
+                (
+                    CHECKPOINT,
+                    vec![("west", "Stables"), ("north", FLOOR)],
+                    vec![],
+                ),
+            ];
+            let rooms = rooms
+                .into_iter()
+                .map(|(name, doors, items)| (name, (doors, items)))
+                .collect();
+            Ship {
+                rooms,
+                room: "Hull Breach",
+                inventory: Vec::new(),
+                stuck: false,
+                looping: false,
+                over: false,
+            }
+        }
+
+        fn describe(&self, name: &str) -> String {
+            let mut s = format!("\n\n\n== {} ==\nA room.\n\nDoors here lead:\n", name);
+            let (doors, items) = &self.rooms[name];
+            for (direction, _) in doors {
+                s += &format!("- {}\n", direction);
+            }
+            if !items.is_empty() {
+                s += "\nItems here:\n";
+                for item in items {
+                    s += &format!("- {}\n", item);
+                }
+            }
+            s + "\nCommand?\n"
+        }
+
+        fn weigh(&mut self) -> String {
+            let floor = format!(
+                "\n\n\n== {} ==\nAnalyzing...\n\nDoors here lead:\n- south\n\n",
+                FLOOR
+            );
+            let weight: usize = self.inventory.iter().map(|item| weight(item)).sum();
+            if weight == TARGET_WEIGHT {
+                self.over = true;
+                return floor
+                    + "\"Oh, hello! You should be able to get in by typing 2424308736 on \
+                        the keypad at the main airlock.\"\n";
+            }
+            let compared = if weight < TARGET_WEIGHT {
+                "heavier"
+            } else {
+                "lighter"
+            };
+            floor
+                + &format!(
+                    "A loud, robotic voice says \"Alert! Droids on this ship are {} than \
+                     the detected value!\" and you are ejected back to the checkpoint.\n",
+                    compared
+                )
+                + &self.describe(CHECKPOINT)
+        }
+    }
+
+    impl Game for Ship {
+        fn send(&mut self, command: &str) -> Result<Option<String>, SolveError> {
+            Ok(self.respond(command))
+        }
+
+        fn is_over(&self) -> bool {
+            self.over
+        }
+    }
+
+    impl Ship {
+        fn respond(&mut self, command: &str) -> Option<String> {
+            assert!(!self.over);
+            if self.looping {
+                return None;
+            }
+            if command.is_empty() {
+                return Some(self.describe(self.room));
+            }
+            if let Some(item) = command.strip_prefix("take ") {
+                let items = &mut self.rooms.get_mut(self.room).unwrap().1;
+                let i = items.iter().position(|i| *i == item).unwrap();
+                let item = items.remove(i);
+                self.inventory.push(item);
+                return match item {
+                    "molten lava" => {
+                        self.over = true;
+                        Some("\nThe molten lava is way too hot! You melt!\n".to_string())
+                    }
+                    "infinite loop" => {
+                        self.looping = true;
+                        None
+                    }
+                    _ => {
+                        self.stuck |= item == "giant electromagnet";
+                        Some(format!("\nYou take the {}.\n\nCommand?\n", item))
+                    }
+                };
+            }
+            if let Some(item) = command.strip_prefix("drop ") {
+                let i = self.inventory.iter().position(|i| *i == item).unwrap();
+                let item = self.inventory.remove(i);
+                self.rooms.get_mut(self.room).unwrap().1.push(item);
+                return Some(format!("\nYou drop the {}.\n\nCommand?\n", item));
+            }
+            if self.stuck {
+                return Some(
+                    "\nThe giant electromagnet is stuck to you.  You can't move!!\n\nCommand?\n"
+                        .to_string(),
+                );
+            }
+            let (doors, _) = &self.rooms[self.room];
+            let (_, room) = doors.iter().find(|(d, _)| *d == command).unwrap();
+            if *room == FLOOR {
+                return Some(self.weigh());
+            }
+            self.room = room;
+            Some(self.describe(room))
+        }
+    }
+
+    #[test]
+    fn test_parse_rooms() {
+        let ship = Ship::new();
+        let rooms = parse_rooms(&ship.describe("Kitchen"));
+        let expected = Room {
+            name: "Kitchen".to_string(),
+            doors: vec!["south".to_string(), "north".to_string(), "west".to_string()],
+            items: vec!["mug".to_string()],
+        };
+        assert_eq!(rooms, vec![expected]);
+
+        let mut ship = Ship::new();
+        let rooms = parse_rooms(&ship.weigh());
+        let names: Vec<_> = rooms.iter().map(|room| room.name.as_str()).collect();
+        assert_eq!(names, vec![FLOOR, CHECKPOINT]);
+    }
+
+    #[test]
+    fn test_parse_password() {
+        let mut ship = Ship::new();
+        ship.inventory = vec!["mug", "weather machine", "dark matter"];
+        assert_eq!(
+            parse_password(&ship.weigh()),
+            Some("2424308736".to_string())
+        );
+        assert_eq!(parse_password("You take the mug."), None);
+    }
+
+    #[test]
+    fn test_explore() {
+        let mut explorer = Explorer::new(Ship::new()).unwrap();
+        explorer.explore().unwrap();
+        assert_eq!(explorer.room, "Hull Breach");
+        assert_eq!(explorer.rooms.len(), 6);
+        assert_eq!(explorer.floor, Some("north".to_string()));
+
+        let mut inventory = explorer.inventory.clone();
+        inventory.sort();
+        assert_eq!(
+            inventory,
+            vec!["dark matter", "hologram", "mug", "weather machine"]
+        );
+        let mut blacklist: Vec<_> = explorer.blacklist.iter().cloned().collect();
+        blacklist.sort();
+        assert_eq!(
+            blacklist,
+            vec!["giant electromagnet", "infinite loop", "molten lava"]
+        );
+
+        assert_eq!(
+            explorer.path_to(CHECKPOINT),
+            Some(vec!["east".to_string(), "east".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_opposite() {
+        assert_eq!(opposite("east"), Ok("west"));
+        assert_eq!(
+            opposite("up"),
+            Err(SolveError::Runtime(
+                "unknown door direction: \"up\"".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_play() {
+        assert_eq!(play(Ship::new()), Ok(Some("2424308736".to_string())));
+
+        let droid = Droid::new(day5::parse_input("77").unwrap());
+        let err = day5::IntcodeError::Opcode {
+            position: 0,
+            instruction: 77,
+        };
+        assert_eq!(play(droid), Err(err.into()));
+    }
+}
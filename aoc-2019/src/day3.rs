@@ -1,8 +1,10 @@
 // https://adventofcode.com/2019/day/3
 
-use std::collections::HashMap;
+use aoc_common::{
+    manhattan_distance, parse_lines, parse_with, Answer, Color, Drawing, ParseError, Parser, Point,
+    Solution, SolveError, Svg,
+};
 use std::collections::HashSet;
-use std::str::FromStr;
 
 /// A direction on the graph
 #[derive(Debug, PartialEq)]
@@ -13,26 +15,18 @@ enum Direction {
     Down,
 }
 
-/// A point on the graph.
-#[derive(Clone, Debug, Hash, Eq, PartialEq)]
-struct Point(i32, i32);
-
-impl Point {
-    fn shift(&self, direction: &Direction) -> Point {
-        match direction {
-            Direction::Left => Point(self.0 - 1, self.1),
-            Direction::Right => Point(self.0 + 1, self.1),
-            Direction::Up => Point(self.0, self.1 + 1),
-            Direction::Down => Point(self.0, self.1 - 1),
+impl Direction {
+    /// Returns the change in position for one step in this direction
+    fn offset(&self) -> Point<i32> {
+        match self {
+            Direction::Left => Point(-1, 0),
+            Direction::Right => Point(1, 0),
+            Direction::Up => Point(0, 1),
+            Direction::Down => Point(0, -1),
         }
     }
 }
 
-/// Calculate the Manhattan distance between two points.
-fn manhattan_distance(p1: &Point, p2: &Point) -> i32 {
-    (p1.0 - p2.0).abs() + (p1.1 - p2.1).abs()
-}
-
 /// A change in position.
 #[derive(Debug, PartialEq)]
 struct Shift {
@@ -40,102 +34,295 @@ struct Shift {
     length: usize,
 }
 
-impl FromStr for Shift {
-    type Err = std::num::ParseIntError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (direction, length) = s.split_at(1);
-        let direction = match direction {
-            "L" => Direction::Left,
-            "R" => Direction::Right,
-            "U" => Direction::Up,
-            "D" => Direction::Down,
-            c => panic!("bad direction: {}", c),
-        };
-        let length = length.parse::<usize>()?;
+impl Shift {
+    /// Parses a shift like "R8"
+    fn parse(p: &mut Parser) -> Result<Self, ParseError> {
+        let direction = p.satisfy("a direction", |c| match c {
+            'L' => Some(Direction::Left),
+            'R' => Some(Direction::Right),
+            'U' => Some(Direction::Up),
+            'D' => Some(Direction::Down),
+            _ => None,
+        })?;
+        let length = p.unsigned()?;
         Ok(Shift { direction, length })
     }
 }
 
-fn points_along_shift(start: &Point, m: &Shift) -> Vec<Point> {
-    let mut points = vec![];
-    let mut position = start.clone();
-    for _ in 0..m.length {
-        position = position.shift(&m.direction);
-        points.push(position.clone())
+/// Returns the corners of a path, from the start to the end
+fn vertices(start: &Point<i32>, path: &[Shift]) -> Vec<Point<i32>> {
+    let mut vertices = vec![*start];
+    let mut position = *start;
+    for shift in path {
+        let Point(dx, dy) = shift.direction.offset();
+        let length = shift.length as i32;
+        position = position + Point(dx * length, dy * length);
+        vertices.push(position);
     }
-    points
+    vertices
 }
 
-fn points_along_path(start: &Point, path: &[Shift]) -> Vec<Point> {
-    let mut all_points: Vec<Point> = vec![];
-    let mut position = start.clone();
-    for shift in path {
-        let mut points = points_along_shift(&position, &shift);
-        if let Some(point) = points.last() {
-            position = point.clone()
-        }
-        all_points.append(&mut points)
+/// A straight part of a wire. It covers the points after `start` up to and
+/// including `end`, so consecutive segments never share a point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Segment {
+    start: Point<i32>,
+    end: Point<i32>,
+    /// The number of steps along the wire to `start`
+    steps: usize,
+}
+
+impl Segment {
+    fn length(&self) -> usize {
+        manhattan_distance(&self.start, &self.end) as usize
+    }
+
+    fn contains(&self, point: &Point<i32>) -> bool {
+        let Point(x, y) = *point;
+        let (x1, x2) = (self.start.0.min(self.end.0), self.start.0.max(self.end.0));
+        let (y1, y2) = (self.start.1.min(self.end.1), self.start.1.max(self.end.1));
+        *point != self.start && x1 <= x && x <= x2 && y1 <= y && y <= y2
+    }
+
+    /// Returns the number of steps along the wire to a point on the segment
+    fn steps_to(&self, point: &Point<i32>) -> usize {
+        self.steps + manhattan_distance(&self.start, point) as usize
+    }
+
+    /// Returns the points in order along the segment
+    fn points(&self) -> impl Iterator<Item = Point<i32>> {
+        let Point(dx, dy) = self.end - self.start;
+        let offset = Point(dx.signum(), dy.signum());
+        let start = self.start;
+        (1..=self.length() as i32).map(move |i| start + Point(offset.0 * i, offset.1 * i))
+    }
+
+    /// Returns the points two segments share. Segments that cross share one
+    /// point, but segments along the same line can overlap.
+    fn intersections(&self, other: &Segment) -> Vec<Point<i32>> {
+        let low = |a: i32, b: i32, c: i32, d: i32| a.min(b).max(c.min(d));
+        let high = |a: i32, b: i32, c: i32, d: i32| a.max(b).min(c.max(d));
+        let (x1, x2) = (
+            low(self.start.0, self.end.0, other.start.0, other.end.0),
+            high(self.start.0, self.end.0, other.start.0, other.end.0),
+        );
+        let (y1, y2) = (
+            low(self.start.1, self.end.1, other.start.1, other.end.1),
+            high(self.start.1, self.end.1, other.start.1, other.end.1),
+        );
+        // the overlap of two axis-aligned segments is a point or a line
+        (x1..=x2)
+            .flat_map(|x| (y1..=y2).map(move |y| Point(x, y)))
+            .filter(|point| self.contains(point) && other.contains(point))
+            .collect()
     }
-    all_points
 }
 
-fn common_points(paths: Vec<Vec<Shift>>) -> HashSet<Point> {
-    let mut point_sets = paths
-        .iter()
-        .map(|path| points_along_path(&Point(0, 0), &path).into_iter().collect());
-    let mut common: HashSet<Point> = HashSet::new();
-    if let Some(points) = point_sets.next() {
-        common = points;
-        for points in point_sets {
-            common = common.intersection(&points).cloned().collect();
-        }
+/// Splits a path into its segments, with the step count at the start of each
+fn segments(start: &Point<i32>, path: &[Shift]) -> Vec<Segment> {
+    let mut steps = 0;
+    vertices(start, path)
+        .windows(2)
+        .map(|ends| {
+            let segment = Segment {
+                start: ends[0],
+                end: ends[1],
+                steps,
+            };
+            steps += segment.length();
+            segment
+        })
+        .collect()
+}
+
+/// A point shared by several wires
+#[derive(Debug, PartialEq)]
+struct Shared {
+    point: Point<i32>,
+    /// The steps along each wire to its first visit of the point, if it
+    /// visits it at all
+    steps: Vec<Option<usize>>,
+}
+
+impl Shared {
+    /// Returns the number of wires that visit the point
+    fn wires(&self) -> usize {
+        self.steps.iter().flatten().count()
+    }
+
+    /// Returns the steps to the point added up over the wires that visit it
+    fn total_steps(&self) -> usize {
+        self.steps.iter().flatten().sum()
+    }
+}
+
+/// A set of wires leaving the central port, each split into segments
+struct WireSet {
+    wires: Vec<Vec<Segment>>,
+}
+
+impl WireSet {
+    fn new(paths: &[Vec<Shift>]) -> Self {
+        let wires = paths
+            .iter()
+            .map(|path| segments(&Point(0, 0), path))
+            .collect();
+        WireSet { wires }
+    }
+
+    fn len(&self) -> usize {
+        self.wires.len()
+    }
+
+    /// Returns the steps along a wire to the first time it visits a point.
+    /// The segments are in order along the wire, so the first one that
+    /// contains the point has the fewest steps.
+    fn first_steps(&self, wire: usize, point: &Point<i32>) -> Option<usize> {
+        self.wires[wire]
+            .iter()
+            .find(|segment| segment.contains(point))
+            .map(|segment| segment.steps_to(point))
+    }
+
+    /// Returns the points visited by at least `k` different wires, in order,
+    /// with the steps along each wire to its first visit. A wire crossing
+    /// itself only counts once.
+    fn shared(&self, k: usize) -> Vec<Shared> {
+        let candidates: HashSet<_> = if k <= 1 {
+            self.wires
+                .iter()
+                .flatten()
+                .flat_map(Segment::points)
+                .collect()
+        } else {
+            // every point shared by two wires is where a pair of their
+            // segments meets
+            let wires = &self.wires;
+            (0..wires.len())
+                .flat_map(|i| (i + 1..wires.len()).map(move |j| (&wires[i], &wires[j])))
+                .flat_map(|(first, second)| {
+                    first
+                        .iter()
+                        .flat_map(move |a| second.iter().flat_map(move |b| a.intersections(b)))
+                })
+                .collect()
+        };
+        let mut shared: Vec<_> = candidates
+            .into_iter()
+            .map(|point| Shared {
+                point,
+                steps: (0..self.len())
+                    .map(|wire| self.first_steps(wire, &point))
+                    .collect(),
+            })
+            .filter(|shared| shared.wires() >= k)
+            .collect();
+        shared.sort_by_key(|shared| shared.point);
+        shared
     }
-    common
 }
 
 fn min_distance(paths: Vec<Vec<Shift>>) -> Option<i32> {
-    let common = common_points(paths);
-    common
+    let wires = WireSet::new(&paths);
+    wires
+        .shared(wires.len())
         .into_iter()
-        .map(|point| manhattan_distance(&Point(0, 0), &point))
+        .map(|shared| manhattan_distance(&Point(0, 0), &shared.point))
         .min()
 }
 
-fn common_points_with_steps(paths: Vec<Vec<Shift>>) -> HashMap<Point, usize> {
-    let mut steps: HashMap<Point, usize> = HashMap::new();
-    for path in &paths {
-        let points = points_along_path(&Point(0, 0), &path);
-        for (step, point) in points.iter().enumerate() {
-            let s = steps.entry(point.clone()).or_insert(0);
-            // the points are zero-indexed, so add one
-            *s += step + 1;
-        }
+fn min_steps(paths: Vec<Vec<Shift>>) -> Option<usize> {
+    let wires = WireSet::new(&paths);
+    wires
+        .shared(wires.len())
+        .iter()
+        .map(Shared::total_steps)
+        .min()
+}
+
+/// The color of the intersection closest to the central port
+const CLOSEST: Color = Color(255, 0, 0);
+
+/// The color of the intersection with the fewest combined steps
+const FEWEST_STEPS: Color = Color(0, 160, 0);
+
+/// Draws each wire in its own color, starting from the central port in
+/// black. Intersections are marked in gray, with the closest one and the one
+/// with the fewest combined steps highlighted.
+fn draw(paths: &[Vec<Shift>]) -> Svg {
+    // the y axis points down in SVG
+    let to_svg = |Point(x, y): Point<i32>| Point(x as i64, -y as i64);
+    let wires: Vec<Vec<_>> = paths
+        .iter()
+        .map(|path| {
+            vertices(&Point(0, 0), path)
+                .into_iter()
+                .map(to_svg)
+                .collect()
+        })
+        .collect();
+    let size = wires
+        .iter()
+        .flatten()
+        .map(|Point(x, y)| x.abs().max(y.abs()))
+        .max()
+        .unwrap_or(0);
+    let radius = (size / 200).max(1);
+
+    let mut svg = Svg::new(radius * 4);
+    for (i, wire) in wires.iter().enumerate() {
+        svg.polyline(wire, Color::indexed(i + 3), 1);
+    }
+    svg.circle(Point(0, 0), radius * 2, Color::BLACK);
+
+    let common = WireSet::new(paths).shared(paths.len());
+    for shared in &common {
+        svg.circle(to_svg(shared.point), radius, Color::gray(128));
     }
-    let common = common_points(paths);
-    steps.retain(|point, _steps| common.contains(&point));
-    steps
+    let closest = common
+        .iter()
+        .min_by_key(|shared| manhattan_distance(&Point(0, 0), &shared.point));
+    if let Some(shared) = closest {
+        svg.circle(to_svg(shared.point), radius * 2, CLOSEST);
+    }
+    let fewest_steps = common.iter().min_by_key(|shared| shared.total_steps());
+    if let Some(shared) = fewest_steps {
+        svg.circle(to_svg(shared.point), radius * 2, FEWEST_STEPS);
+    }
+    svg
 }
 
-fn min_steps(paths: Vec<Vec<Shift>>) -> Option<usize> {
-    let common = common_points_with_steps(paths);
-    common.into_iter().map(|(_point, step)| step).min()
+/// Parses one wire per line, each a comma-separated list of shifts
+fn parse_input(input: &str) -> Result<Vec<Vec<Shift>>, ParseError> {
+    parse_lines(input, |line| {
+        parse_with(line, |p: &mut Parser| p.separated(",", Shift::parse))
+    })
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(min_distance(parse_input(input)?).into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(min_steps(parse_input(input)?).into())
+    }
+
+    fn draw(&self, input: &str) -> Result<Option<Drawing>, SolveError> {
+        Ok(Some(Drawing::Svg(draw(&parse_input(input)?))))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    fn parse_input(input: &str) -> Vec<Vec<Shift>> {
-        input
-            .lines()
-            .map(|line| {
-                line.split(",")
-                    .map(|s| s.parse::<Shift>().unwrap())
-                    .collect()
-            })
-            .collect()
-    }
+    use aoc_common::INPUT;
 
     #[test]
     fn test_manhattan_distance() {
@@ -157,10 +344,10 @@ mod tests {
                 "R75,D30,R83,U83,L12,D49,R71,U7,L72\nU62,R66,U55,R34,D71,R55,D58,R83",
                 Some(159),
             ),
-            (include_str!("day3.input"), Some(1264)),
+            (crate::input(3, INPUT), Some(1264)),
         ];
         for (input, expected) in tests {
-            let input = parse_input(input);
+            let input = parse_input(input).unwrap();
             assert_eq!(&min_distance(input), expected);
         }
     }
@@ -177,11 +364,134 @@ mod tests {
                 "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51\nU98,R91,D20,R16,D67,R40,U7,R15,U6,R7",
                 Some(410),
             ),
-            (include_str!("day3.input"), Some(37390)),
+            (crate::input(3, INPUT), Some(37390)),
         ];
         for (input, expected) in tests {
-            let input = parse_input(input);
+            let input = parse_input(input).unwrap();
             assert_eq!(&min_steps(input), expected);
         }
     }
+
+    #[test]
+    fn test_vertices() {
+        let path = &parse_input("R8,U5,L5,D3").unwrap()[0];
+        assert_eq!(
+            vertices(&Point(0, 0), path),
+            vec![
+                Point(0, 0),
+                Point(8, 0),
+                Point(8, 5),
+                Point(3, 5),
+                Point(3, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_segments() {
+        let path = &parse_input("R8,U5,L5").unwrap()[0];
+        let segment = |start, end, steps| Segment { start, end, steps };
+        assert_eq!(
+            segments(&Point(0, 0), path),
+            vec![
+                segment(Point(0, 0), Point(8, 0), 0),
+                segment(Point(8, 0), Point(8, 5), 8),
+                segment(Point(8, 5), Point(3, 5), 13),
+            ]
+        );
+        let up = segment(Point(8, 0), Point(8, 5), 8);
+        assert_eq!(up.steps_to(&Point(8, 3)), 11);
+        assert!(up.contains(&Point(8, 5)));
+        assert!(!up.contains(&Point(8, 0)));
+        assert_eq!(
+            up.points().collect::<Vec<_>>(),
+            vec![
+                Point(8, 1),
+                Point(8, 2),
+                Point(8, 3),
+                Point(8, 4),
+                Point(8, 5)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_intersections() {
+        let segment = |start, end| Segment {
+            start,
+            end,
+            steps: 0,
+        };
+        let across = segment(Point(0, 2), Point(6, 2));
+        let up = segment(Point(3, 0), Point(3, 5));
+        assert_eq!(across.intersections(&up), vec![Point(3, 2)]);
+        assert_eq!(up.intersections(&across), vec![Point(3, 2)]);
+
+        // the start of a segment belongs to the one before it
+        let up = segment(Point(3, 2), Point(3, 5));
+        assert_eq!(across.intersections(&up), vec![]);
+
+        let back = segment(Point(8, 2), Point(4, 2));
+        assert_eq!(
+            across.intersections(&back),
+            vec![Point(4, 2), Point(5, 2), Point(6, 2)]
+        );
+        let parallel = segment(Point(0, 3), Point(6, 3));
+        assert_eq!(across.intersections(&parallel), vec![]);
+    }
+
+    #[test]
+    fn test_shared() {
+        // the first wire crosses itself at 2,0 and runs down over the
+        // second wire, and the third wire meets neither
+        let paths = parse_input("R4,U2,L2,D4\nU1,R2,D3\nD1").unwrap();
+        let wires = WireSet::new(&paths);
+        let shared = |x, y, steps: &[usize]| Shared {
+            point: Point(x, y),
+            steps: steps.iter().map(|s| Some(*s)).chain(Some(None)).collect(),
+        };
+        assert_eq!(
+            wires.shared(2),
+            vec![
+                shared(2, -2, &[12, 6]),
+                shared(2, -1, &[11, 5]),
+                shared(2, 0, &[2, 4]),
+                shared(2, 1, &[9, 3]),
+            ]
+        );
+        assert_eq!(wires.shared(3), vec![]);
+        assert_eq!(wires.first_steps(0, &Point(2, 0)), Some(2));
+        assert_eq!(wires.first_steps(2, &Point(2, 0)), None);
+
+        // 12 steps on the first wire visit 11 points, 6 on the second visit
+        // 6 and the third visits 1, with the 4 shared points counted once
+        assert_eq!(wires.shared(1).len(), 11 + 6 + 1 - 4);
+        let paths = parse_input("R4,U2,L2,D4\nU1,R2,D3").unwrap();
+        assert_eq!(min_steps(paths), Some(6));
+    }
+
+    #[test]
+    fn test_draw() {
+        let paths = parse_input("R8,U5,L5,D3\nU7,R6,D4,L4").unwrap();
+        let svg = draw(&paths).to_string();
+        // two wires, the port, two intersections and two highlights
+        assert_eq!(svg.lines().count(), 9);
+        assert!(svg.contains("points=\"0,0 8,0 8,-5 3,-5 3,-2\""));
+        assert!(svg.contains("<circle cx=\"3\" cy=\"-3\" r=\"2\" fill=\"#ff0000\"/>"));
+        assert!(svg.contains("<circle cx=\"6\" cy=\"-5\" r=\"2\" fill=\"#00a000\"/>"));
+    }
+
+    #[test]
+    fn test_parse_input() {
+        let shift = |direction, length| Shift { direction, length };
+        assert_eq!(
+            parse_input("R8,U5\nL5"),
+            Ok(vec![
+                vec![shift(Direction::Right, 8), shift(Direction::Up, 5)],
+                vec![shift(Direction::Left, 5)],
+            ])
+        );
+        let expected = ParseError::new("a direction", "X5").at_column(7).at_line(2);
+        assert_eq!(parse_input("R8,U5\nR8,U5,X5"), Err(expected));
+    }
 }
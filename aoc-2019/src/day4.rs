@@ -1,66 +1,443 @@
 // https://adventofcode.com/2019/day/4
 
+use aoc_common::{
+    parse_digits, parse_number, parse_with, Answer, ParseError, Parser, Solution, SolveError,
+};
 use std::collections::HashMap;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// A rule on the lengths of the runs of equal digits in a password. A
+/// password passes if any of its runs has a length the rule accepts.
+type RunRule = fn(usize) -> bool;
+
+/// Part 1 needs two adjacent digits that are the same
+const AT_LEAST_TWO: RunRule = |run| run >= 2;
+
+/// Part 2 needs two adjacent digits that are not part of a larger group
+const EXACTLY_TWO: RunRule = |run| run == 2;
+
+/// Returns the lengths of the runs of equal digits
+fn runs(pass: &[u8]) -> Vec<usize> {
+    pass.chunk_by(|a, b| a == b).map(<[u8]>::len).collect()
+}
+
+/// Returns whether the digits never decrease and a run passes the rule
+fn passes(pass: &[u8], rule: RunRule) -> bool {
+    pass.windows(2).all(|digits| digits[0] <= digits[1]) && runs(pass).into_iter().any(rule)
+}
 
 fn valid(pass: &[u8]) -> bool {
-    let mut has_double = false;
-    for digits in pass.windows(2) {
-        if digits[1] < digits[0] {
-            return false;
+    passes(pass, AT_LEAST_TWO)
+}
+
+fn valid_2(pass: &[u8]) -> bool {
+    passes(pass, EXACTLY_TWO)
+}
+
+/// The state of a password after some digits: the digit of the current
+/// run, how long the run is, and which rules earlier runs passed, one bit
+/// per rule
+type State = (u8, usize, u32);
+
+/// Counts passwords one digit position at a time. A password is built as
+/// runs of equal digits, so the only state needed is the current run and
+/// which rules earlier runs passed. Each rule must be passed by some run.
+struct Counter {
+    nondecreasing: bool,
+    rules: Vec<Box<dyn Fn(usize) -> bool>>,
+    memo: HashMap<(usize, State), usize>,
+}
+
+impl Counter {
+    fn new(nondecreasing: bool, rules: Vec<Box<dyn Fn(usize) -> bool>>) -> Self {
+        Counter {
+            nondecreasing,
+            rules,
+            memo: HashMap::new(),
+        }
+    }
+
+    /// Returns the rules a run of this length passes
+    fn passes(&self, run: usize) -> u32 {
+        self.rules
+            .iter()
+            .enumerate()
+            .filter(|(_, rule)| rule(run))
+            .fold(0, |passed, (i, _)| passed | 1 << i)
+    }
+
+    fn is_valid(&self, (_, run, passed): State) -> bool {
+        passed | self.passes(run) == (1 << self.rules.len()) - 1
+    }
+
+    /// Returns the state after adding a digit, or `None` if the digits
+    /// must not decrease and this one does
+    fn push(&self, state: Option<State>, digit: u8) -> Option<State> {
+        match state {
+            None => Some((digit, 1, 0)),
+            Some((last, run, passed)) if digit == last => Some((last, run + 1, passed)),
+            Some((last, _, _)) if self.nondecreasing && digit < last => None,
+            Some((_, run, passed)) => Some((digit, 1, passed | self.passes(run))),
+        }
+    }
+
+    /// Returns the number of ways to add `remaining` digits after a state
+    fn complete(&mut self, remaining: usize, state: State) -> usize {
+        if remaining == 0 {
+            return self.is_valid(state) as usize;
+        }
+        let key = (remaining, state);
+        if let Some(count) = self.memo.get(&key) {
+            return *count;
+        }
+        let mut count = 0;
+        for digit in 0..=9 {
+            if let Some(next) = self.push(Some(state), digit) {
+                count += self.complete(remaining - 1, next);
+            }
+        }
+        self.memo.insert(key, count);
+        count
+    }
+
+    /// Returns the digits a number with `length` digits can start with
+    fn first_digits(length: usize) -> RangeInclusive<u8> {
+        if length == 1 {
+            0..=9
+        } else {
+            1..=9
+        }
+    }
+
+    /// Counts the valid passwords with `length` digits
+    fn count_length(&mut self, length: usize) -> usize {
+        Self::first_digits(length)
+            .map(|digit| self.complete(length - 1, (digit, 1, 0)))
+            .sum()
+    }
+
+    /// Counts the valid passwords from 0 to `n`
+    fn count_to(&mut self, n: usize) -> usize {
+        let bound = digits(n);
+        let mut count: usize = (1..bound.len())
+            .map(|length| self.count_length(length))
+            .sum();
+
+        // numbers with as many digits follow the digits of `n` until they
+        // pick a smaller one, after which the rest are free
+        let mut state = None;
+        for (i, &limit) in bound.iter().enumerate() {
+            let remaining = bound.len() - i - 1;
+            let low = if i == 0 {
+                *Self::first_digits(bound.len()).start()
+            } else {
+                0
+            };
+            for digit in low..limit {
+                if let Some(next) = self.push(state, digit) {
+                    count += self.complete(remaining, next);
+                }
+            }
+            state = match self.push(state, limit) {
+                Some(next) => Some(next),
+                None => return count,
+            };
+        }
+        count + state.is_some_and(|state| self.is_valid(state)) as usize
+    }
+
+    /// Returns the valid password with `rank` valid passwords before it,
+    /// choosing one digit at a time by how many passwords follow each
+    fn nth(&mut self, mut rank: usize) -> Option<usize> {
+        // every count fits in a usize for numbers with fewer digits than
+        // the largest usize
+        let mut lengths = 1..digits(usize::MAX).len();
+        let length = lengths.find(|length| {
+            let count = self.count_length(*length);
+            if rank < count {
+                return true;
+            }
+            rank -= count;
+            false
+        })?;
+
+        let mut state = None;
+        let mut n = 0;
+        for remaining in (0..length).rev() {
+            let low = if state.is_none() {
+                *Self::first_digits(length).start()
+            } else {
+                0
+            };
+            for digit in low..=9 {
+                let next = match self.push(state, digit) {
+                    Some(next) => next,
+                    None => continue,
+                };
+                let count = self.complete(remaining, next);
+                if rank < count {
+                    state = Some(next);
+                    n = n * 10 + digit as usize;
+                    break;
+                }
+                rank -= count;
+            }
         }
-        if digits[0] == digits[1] {
-            has_double = true
+        Some(n)
+    }
+
+    /// Counts the valid passwords in a range
+    fn count(&mut self, range: &RangeInclusive<usize>) -> usize {
+        match (*range.start(), *range.end()) {
+            (start, end) if start > end => 0,
+            (0, end) => self.count_to(end),
+            (start, end) => self.count_to(end) - self.count_to(start - 1),
         }
     }
-    has_double
 }
 
-fn valid_2(pass: &[u8]) -> bool {
-    let mut counts: HashMap<&u8, usize> = HashMap::new();
-    let mut previous: Option<&u8> = None;
-    for d in pass {
-        if let Some(previous) = previous {
-            if d < previous {
-                return false;
+fn parse_input(s: &str) -> Result<Vec<u8>, ParseError> {
+    Ok(parse_digits(s)?.into_iter().map(|d| d as u8).collect())
+}
+
+/// Returns the decimal digits of `n`
+fn digits(n: usize) -> Vec<u8> {
+    n.to_string().bytes().map(|b| b - b'0').collect()
+}
+
+fn parse_range(s: &str) -> Result<RangeInclusive<usize>, ParseError> {
+    match s.trim().split_once('-') {
+        Some((start, end)) => Ok(parse_number(start)?..=parse_number(end)?),
+        None => Err(ParseError::new("a range like \"123-456\"", s.trim())),
+    }
+}
+
+/// Counts the passwords in the range which are valid, without going
+/// through them one by one
+fn count_valid(range: RangeInclusive<usize>, rule: RunRule) -> usize {
+    Counter::new(true, vec![Box::new(rule)]).count(&range)
+}
+
+/// A rule a password must follow
+#[derive(Debug, Clone, PartialEq)]
+enum Rule {
+    /// The digits never decrease from left to right
+    Nondecreasing,
+    /// Some run of equal digits is exactly this long
+    RunOfExactly(usize),
+    /// Some run of equal digits is at least this long
+    RunOfAtLeast(usize),
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rule::Nondecreasing => write!(f, "nondecreasing"),
+            Rule::RunOfExactly(n) => write!(f, "has run of exactly {}", n),
+            Rule::RunOfAtLeast(n) => write!(f, "has run >= {}", n),
+        }
+    }
+}
+
+impl Rule {
+    /// Parses a rule like "nondecreasing", "has run of exactly 2" or
+    /// "has run >= 2"
+    fn parse(p: &mut Parser) -> Result<Self, ParseError> {
+        if p.try_literal("nondecreasing") {
+            return Ok(Rule::Nondecreasing);
+        }
+        if !p.try_literal("has run ") {
+            return Err(p.error("a rule"));
+        }
+        if p.try_literal("of exactly ") {
+            Ok(Rule::RunOfExactly(p.unsigned()?))
+        } else if p.try_literal(">= ") {
+            Ok(Rule::RunOfAtLeast(p.unsigned()?))
+        } else {
+            Err(p.error("\"of exactly\" or \">=\""))
+        }
+    }
+
+    fn check(&self, pass: &[u8]) -> bool {
+        match self {
+            Rule::Nondecreasing => pass.windows(2).all(|digits| digits[0] <= digits[1]),
+            Rule::RunOfExactly(n) => runs(pass).contains(n),
+            Rule::RunOfAtLeast(n) => runs(pass).iter().any(|run| run >= n),
+        }
+    }
+}
+
+/// Parses a comma-separated list of rules
+fn parse_rules(s: &str) -> Result<Vec<Rule>, ParseError> {
+    parse_with(s.trim(), |p: &mut Parser| {
+        p.separated(",", |p| {
+            p.whitespace();
+            Rule::parse(p)
+        })
+    })
+}
+
+/// Why a password is rejected
+#[derive(Debug, PartialEq)]
+enum Failure<'a> {
+    OutOfRange,
+    Rule(&'a Rule),
+}
+
+/// Returns the smallest number from `n` on whose digits never decrease.
+/// After the first digit that is smaller than the one before it, every
+/// digit is raised to match.
+fn next_nondecreasing(n: usize) -> usize {
+    let mut digits = digits(n);
+    if let Some(i) = digits.windows(2).position(|pair| pair[1] < pair[0]) {
+        let digit = digits[i];
+        digits[i + 1..].iter_mut().for_each(|d| *d = digit);
+    }
+    digits.iter().fold(0, |n, d| n * 10 + *d as usize)
+}
+
+/// A small xorshift random number generator, so samples can be repeated
+/// from the same seed
+struct Random(u64);
+
+impl Random {
+    fn new(seed: u64) -> Self {
+        // xorshift never leaves zero
+        Random(seed.max(1))
+    }
+
+    /// Returns a number from 0 up to but not including `n`
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
+
+/// A range of passwords and the rules they must follow
+struct Policy {
+    range: RangeInclusive<usize>,
+    rules: Vec<Rule>,
+}
+
+impl Policy {
+    fn new(range: RangeInclusive<usize>, rules: Vec<Rule>) -> Self {
+        Policy { range, rules }
+    }
+
+    /// Returns why a password is rejected, which is the first rule it
+    /// breaks
+    fn check(&self, pass: usize) -> Result<(), Failure<'_>> {
+        if !self.range.contains(&pass) {
+            return Err(Failure::OutOfRange);
+        }
+        let digits = digits(pass);
+        match self.rules.iter().find(|rule| !rule.check(&digits)) {
+            Some(rule) => Err(Failure::Rule(rule)),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the valid passwords in order. When the digits must not
+    /// decrease, the numbers that break that rule are skipped over.
+    fn matches(&self) -> impl Iterator<Item = usize> + '_ {
+        let skip = self.rules.contains(&Rule::Nondecreasing);
+        let end = *self.range.end();
+        let mut next = Some(*self.range.start());
+        std::iter::from_fn(move || {
+            while let Some(n) = next {
+                let n = if skip { next_nondecreasing(n) } else { n };
+                if n > end {
+                    break;
+                }
+                next = n.checked_add(1);
+                if self.check(n).is_ok() {
+                    return Some(n);
+                }
             }
+            next = None;
+            None
+        })
+    }
+
+    /// Returns a counter for the rules
+    fn counter(&self) -> Counter {
+        let nondecreasing = self.rules.contains(&Rule::Nondecreasing);
+        let rules = self
+            .rules
+            .iter()
+            .filter_map(|rule| -> Option<Box<dyn Fn(usize) -> bool>> {
+                match *rule {
+                    Rule::Nondecreasing => None,
+                    Rule::RunOfExactly(n) => Some(Box::new(move |run| run == n)),
+                    Rule::RunOfAtLeast(n) => Some(Box::new(move |run| run >= n)),
+                }
+            })
+            .collect();
+        Counter::new(nondecreasing, rules)
+    }
+
+    /// Counts the valid passwords without going through them
+    fn count(&self) -> usize {
+        self.counter().count(&self.range)
+    }
+
+    /// Returns `count` valid passwords picked at random, which may repeat.
+    /// Each is found by its rank among the valid passwords, so this is
+    /// quick however large the range is.
+    fn sample(&self, count: usize, random: &mut Random) -> Vec<usize> {
+        let mut counter = self.counter();
+        let total = counter.count(&self.range);
+        if total == 0 {
+            return vec![];
         }
-        previous = Some(d);
-        let count = counts.entry(d).or_insert(0);
-        *count += 1;
+        let before = match *self.range.start() {
+            0 => 0,
+            start => counter.count_to(start - 1),
+        };
+        (0..count)
+            .filter_map(|_| counter.nth(before + random.below(total)))
+            .collect()
+    }
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_range(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(count_valid(parse_range(input)?, AT_LEAST_TWO).into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(count_valid(parse_range(input)?, EXACTLY_TWO).into())
     }
-    counts.values().any(|count| count == &2)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::INPUT;
 
-    fn parse_input(s: &str) -> Vec<u8> {
-        s.chars()
-            .map(|c| c.to_string().parse::<u8>().unwrap())
-            .collect()
-    }
-
-    fn my_input() -> std::ops::RangeInclusive<usize> {
-        let input = include_str!("day4.input");
-        let parts: Vec<_> = input
-            .split("-")
-            .map(|s| s.trim().parse::<usize>().unwrap())
-            .collect();
-        parts[0]..=parts[1]
+    fn my_input() -> RangeInclusive<usize> {
+        parse_range(crate::input(4, INPUT)).unwrap()
     }
 
     #[test]
     fn test_valid() {
         let tests = &[("111111", true), ("223450", false), ("123789", false)];
         for (input, expected) in tests {
-            let input = parse_input(input);
+            let input = parse_input(input).unwrap();
             assert_eq!(&valid(&input), expected);
         }
 
         let count = my_input()
-            .map(|i| i.to_string())
-            .map(|s| parse_input(&s))
+            .map(digits)
             .map(|pass| valid(&pass))
             .filter(|b| *b)
             .collect::<Vec<_>>()
@@ -72,17 +449,158 @@ mod tests {
     fn test_valid_2() {
         let tests = &[("112233", true), ("123444", false), ("111122", true)];
         for (input, expected) in tests {
-            let input = parse_input(input);
+            let input = parse_input(input).unwrap();
             assert_eq!(&valid_2(&input), expected);
         }
 
         let count = my_input()
-            .map(|i| i.to_string())
-            .map(|s| parse_input(&s))
+            .map(digits)
             .map(|pass| valid_2(&pass))
             .filter(|b| *b)
             .collect::<Vec<_>>()
             .len();
         assert_eq!(count, 603);
     }
+
+    #[test]
+    fn test_count_valid() {
+        assert_eq!(count_valid(my_input(), AT_LEAST_TWO), 921);
+        assert_eq!(count_valid(my_input(), EXACTLY_TWO), 603);
+
+        let brute_force = |range: RangeInclusive<usize>, rule| {
+            range.filter(|n| passes(&digits(*n), rule)).count()
+        };
+        let ranges = &[0..=0, 0..=99, 5..=1234, 111..=111, 111_112..=112_233];
+        for range in ranges {
+            for rule in &[AT_LEAST_TWO, EXACTLY_TWO] {
+                let expected = brute_force(range.clone(), *rule);
+                assert_eq!(count_valid(range.clone(), *rule), expected);
+            }
+        }
+
+        // of the C(l + 8, 8) nondecreasing numbers with l digits, the
+        // C(9, l) strictly increasing ones have no pair
+        assert_eq!(
+            count_valid(0..=999_999_999_999_999, AT_LEAST_TWO),
+            1_306_992
+        );
+    }
+
+    #[test]
+    fn test_runs() {
+        assert_eq!(runs(&[1, 1, 2, 3, 3, 3]), vec![2, 1, 3]);
+        assert_eq!(runs(&[]), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_rules() {
+        let rules = parse_rules("nondecreasing, has run of exactly 2,has run >= 3\n").unwrap();
+        assert_eq!(
+            rules,
+            vec![
+                Rule::Nondecreasing,
+                Rule::RunOfExactly(2),
+                Rule::RunOfAtLeast(3)
+            ]
+        );
+        let text: Vec<_> = rules.iter().map(Rule::to_string).collect();
+        assert_eq!(
+            text.join(", "),
+            "nondecreasing, has run of exactly 2, has run >= 3"
+        );
+
+        assert_eq!(
+            parse_rules("increasing"),
+            Err(ParseError::new("a rule", "increasing").at_column(1))
+        );
+        assert_eq!(
+            parse_rules("nondecreasing, has run < 2"),
+            Err(ParseError::new("\"of exactly\" or \">=\"", "<").at_column(24))
+        );
+    }
+
+    #[test]
+    fn test_policy() {
+        let rules = parse_rules("nondecreasing, has run of exactly 2").unwrap();
+        let policy = Policy::new(100_000..=999_999, rules.clone());
+        assert_eq!(policy.check(112233), Ok(()));
+        assert_eq!(policy.check(11223), Err(Failure::OutOfRange));
+        assert_eq!(policy.check(223450), Err(Failure::Rule(&rules[0])));
+        assert_eq!(policy.check(123444), Err(Failure::Rule(&rules[1])));
+
+        let policy = Policy::new(my_input(), rules);
+        assert_eq!(policy.matches().count(), 603);
+
+        let policy = Policy::new(100..=300, parse_rules("has run >= 2").unwrap());
+        let expected: Vec<_> = (100..=300)
+            .filter(|n| runs(&digits(*n)).iter().any(|run| *run >= 2))
+            .collect();
+        assert_eq!(policy.matches().collect::<Vec<_>>(), expected);
+        let policy = Policy::new(100..=300, parse_rules("nondecreasing").unwrap());
+        assert_eq!(
+            policy.matches().take(3).collect::<Vec<_>>(),
+            vec![111, 112, 113]
+        );
+        assert_eq!(policy.matches().last(), Some(299));
+    }
+
+    #[test]
+    fn test_sample() {
+        let rules = parse_rules("nondecreasing, has run >= 2").unwrap();
+        let policy = Policy::new(my_input(), rules);
+        let sample = policy.sample(10, &mut Random::new(4));
+        assert_eq!(sample.len(), 10);
+        assert!(sample.iter().all(|pass| policy.check(*pass).is_ok()));
+        assert_eq!(policy.sample(10, &mut Random::new(4)), sample);
+
+        let policy = Policy::new(10..=20, parse_rules("has run >= 3").unwrap());
+        assert_eq!(policy.sample(10, &mut Random::new(4)), vec![]);
+
+        // without a nondecreasing rule, and over a huge range
+        let rules = parse_rules("has run of exactly 2, has run >= 4").unwrap();
+        let policy = Policy::new(10usize.pow(14)..=10usize.pow(17), rules);
+        let sample = policy.sample(10, &mut Random::new(4));
+        assert_eq!(sample.len(), 10);
+        assert!(sample.iter().all(|pass| policy.check(*pass).is_ok()));
+    }
+
+    #[test]
+    fn test_nth() {
+        for rules in &["nondecreasing, has run >= 2", "has run of exactly 3", ""] {
+            let rules = if rules.is_empty() {
+                vec![]
+            } else {
+                parse_rules(rules).unwrap()
+            };
+            let policy = Policy::new(0..=20_000, rules);
+            let matches: Vec<_> = policy.matches().collect();
+            assert_eq!(policy.count(), matches.len());
+            let mut counter = policy.counter();
+            for (rank, pass) in matches.iter().enumerate() {
+                assert_eq!(counter.nth(rank), Some(*pass));
+            }
+        }
+    }
+
+    #[test]
+    fn test_next_nondecreasing() {
+        assert_eq!(next_nondecreasing(123), 123);
+        assert_eq!(next_nondecreasing(130), 133);
+        assert_eq!(next_nondecreasing(183_045), 188_888);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse_input("1230"), Ok(vec![1, 2, 3, 0]));
+        assert_eq!(
+            parse_input("12a"),
+            Err(ParseError::new("a digit", "a").at_column(3))
+        );
+        assert_eq!(parse_range("100-200\n"), Ok(100..=200));
+        assert_eq!(
+            parse_range("100"),
+            Err(ParseError::new("a range like \"123-456\"", "100"))
+        );
+        assert_eq!(digits(1203), vec![1, 2, 0, 3]);
+    }
 }
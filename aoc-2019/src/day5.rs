@@ -1,10 +1,80 @@
 // https://adventofcode.com/2019/day/5
 
-pub fn parse_input(s: &str) -> Vec<i64> {
-    s.trim_end()
-        .split(',')
-        .map(|s| s.parse().unwrap())
-        .collect()
+use aoc_common::{parse_comma_separated, Answer, ParseError, Solution, SolveError};
+use std::convert::TryFrom;
+use std::fmt;
+
+pub fn parse_input(s: &str) -> Result<Vec<i64>, ParseError> {
+    let program = parse_comma_separated(s)?;
+    if program.is_empty() {
+        return Err(ParseError::new("an Intcode program", s.trim()));
+    }
+    Ok(program)
+}
+
+/// Encodes a string as program input, one ASCII code per value
+pub fn ascii_input(s: &str) -> Vec<i64> {
+    s.bytes().map(i64::from).collect()
+}
+
+/// Splits program output into ASCII text, and any values outside of the
+/// ASCII range
+pub fn ascii_output(outputs: &[i64]) -> (String, Vec<i64>) {
+    let (text, other): (Vec<i64>, Vec<i64>) = outputs.iter().partition(|n| (0..128).contains(*n));
+    let text = text.into_iter().map(|n| n as u8 as char).collect();
+    (text, other)
+}
+
+/// A fault in a running Intcode program. Each fault records the position
+/// of the instruction which caused it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntcodeError {
+    /// An instruction with an unknown opcode
+    Opcode { position: usize, instruction: i64 },
+    /// An instruction with an unknown parameter mode
+    ParameterMode { position: usize, instruction: i64 },
+    /// An instruction which refers to an address outside of memory
+    Address { position: usize, address: i64 },
+    /// The program ran for the given number of steps without halting or
+    /// waiting for input
+    Timeout { steps: usize },
+}
+
+impl fmt::Display for IntcodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntcodeError::Opcode {
+                position,
+                instruction,
+            } => write!(
+                f,
+                "unknown opcode in instruction {} at position {}",
+                instruction, position
+            ),
+            IntcodeError::ParameterMode {
+                position,
+                instruction,
+            } => write!(
+                f,
+                "unknown parameter mode in instruction {} at position {}",
+                instruction, position
+            ),
+            IntcodeError::Address { position, address } => write!(
+                f,
+                "invalid address {} in the instruction at position {}",
+                address, position
+            ),
+            IntcodeError::Timeout { steps } => {
+                write!(f, "the program did not stop within {} steps", steps)
+            }
+        }
+    }
+}
+
+impl From<IntcodeError> for SolveError {
+    fn from(err: IntcodeError) -> Self {
+        SolveError::Runtime(format!("Intcode program failed: {}", err))
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -22,19 +92,19 @@ enum Opcode {
 }
 
 impl Opcode {
-    fn from_u8(i: u8) -> Self {
+    fn from_u8(i: u8) -> Option<Self> {
         match i {
-            1 => Self::Add,
-            2 => Self::Mult,
-            3 => Self::Input,
-            4 => Self::Output,
-            5 => Self::JumpIfTrue,
-            6 => Self::JumpIfFalse,
-            7 => Self::LessThan,
-            8 => Self::Equals,
-            9 => Self::AdjRelBase,
-            99 => Self::Halt,
-            _ => panic!("invalid opcode: {}", i),
+            1 => Some(Self::Add),
+            2 => Some(Self::Mult),
+            3 => Some(Self::Input),
+            4 => Some(Self::Output),
+            5 => Some(Self::JumpIfTrue),
+            6 => Some(Self::JumpIfFalse),
+            7 => Some(Self::LessThan),
+            8 => Some(Self::Equals),
+            9 => Some(Self::AdjRelBase),
+            99 => Some(Self::Halt),
+            _ => None,
         }
     }
 }
@@ -47,31 +117,44 @@ enum ParameterMode {
 }
 
 impl ParameterMode {
-    fn from_u8(i: u8) -> Self {
+    fn from_u8(i: u8) -> Option<Self> {
         match i {
-            0 => Self::Position,
-            1 => Self::Immediate,
-            2 => Self::Relative,
-            _ => panic!("invalid parameter: {}", i),
+            0 => Some(Self::Position),
+            1 => Some(Self::Immediate),
+            2 => Some(Self::Relative),
+            _ => None,
         }
     }
 }
 
-fn parse_instruction(s: &str) -> (Opcode, Vec<ParameterMode>) {
-    if s.len() < 2 {
-        return (Opcode::from_u8(s.parse::<u8>().unwrap()), vec![]);
+/// Decodes the instruction at `position` into its opcode and the modes of
+/// its parameters. Fails on an unknown opcode or parameter mode.
+fn parse_instruction(
+    instruction: i64,
+    position: usize,
+) -> Result<(Opcode, Vec<ParameterMode>), IntcodeError> {
+    let opcode = Some(instruction)
+        .filter(|instruction| *instruction >= 0)
+        .and_then(|instruction| Opcode::from_u8((instruction % 100) as u8))
+        .ok_or(IntcodeError::Opcode {
+            position,
+            instruction,
+        })?;
+    let mut modes = instruction / 100;
+    let mut params = Vec::new();
+    while modes > 0 {
+        let mode =
+            ParameterMode::from_u8((modes % 10) as u8).ok_or(IntcodeError::ParameterMode {
+                position,
+                instruction,
+            })?;
+        params.push(mode);
+        modes /= 10;
     }
-    let (params, opcode) = s.split_at(s.len() - 2);
-    let opcode = Opcode::from_u8(opcode.parse::<u8>().unwrap());
-    let params: Vec<_> = params
-        .chars()
-        .rev()
-        .map(|c| ParameterMode::from_u8(c.to_digit(10).unwrap() as u8))
-        .collect();
-    (opcode, params)
+    Ok((opcode, params))
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Program {
     program: Vec<i64>,
     position: usize,
@@ -111,65 +194,80 @@ impl Program {
         }
     }
 
-    pub fn run(&mut self, inputs: &[i64]) -> Vec<i64> {
+    /// Returns the address a parameter writes to. Fails if the address is
+    /// negative.
+    fn get_address(&self, pos: usize, mode: &ParameterMode) -> Result<usize, IntcodeError> {
+        let address = match mode {
+            ParameterMode::Position | ParameterMode::Immediate => self.get(pos),
+            ParameterMode::Relative => self.get(pos) + self.relative_base,
+        };
+        usize::try_from(address).map_err(|_| IntcodeError::Address {
+            position: self.position,
+            address,
+        })
+    }
+
+    /// Runs the program until it halts or needs more input, and returns its
+    /// outputs. Fails if the program reaches an invalid instruction.
+    pub fn run(&mut self, inputs: &[i64]) -> Result<Vec<i64>, IntcodeError> {
+        self.run_for(inputs, usize::MAX)
+    }
+
+    /// Runs the program like `run`, but fails with a timeout after executing
+    /// `max_steps` instructions
+    pub fn run_for(&mut self, inputs: &[i64], max_steps: usize) -> Result<Vec<i64>, IntcodeError> {
         let mut inputs = inputs.iter();
         let mut outputs: Vec<i64> = Vec::new();
-        loop {
-            // dbg!(&self);
-            dbg!(
-                &outputs,
-                &self.position,
-                &self.program[self.position..]
-                    .iter()
-                    .take(5)
-                    .collect::<Vec<_>>(),
-            );
-            let instruction = self.program[self.position].to_string();
-            match parse_instruction(&instruction) {
+        for _ in 0..max_steps {
+            match parse_instruction(self.get(self.position), self.position)? {
                 (Opcode::Add, param_modes) => {
                     let a = self.get_value(
                         self.position + 1,
-                        param_modes.get(0).unwrap_or(&ParameterMode::Position),
+                        param_modes.first().unwrap_or(&ParameterMode::Position),
                     );
                     let b = self.get_value(
                         self.position + 2,
                         param_modes.get(1).unwrap_or(&ParameterMode::Position),
                     );
-                    let target = self.program[self.position + 3] as usize;
+                    let target = self.get_address(
+                        self.position + 3,
+                        param_modes.get(2).unwrap_or(&ParameterMode::Position),
+                    )?;
                     self.put(target, a + b);
                     self.position += 4
                 }
                 (Opcode::Mult, param_modes) => {
                     let a = self.get_value(
                         self.position + 1,
-                        param_modes.get(0).unwrap_or(&ParameterMode::Position),
+                        param_modes.first().unwrap_or(&ParameterMode::Position),
                     );
                     let b = self.get_value(
                         self.position + 2,
                         param_modes.get(1).unwrap_or(&ParameterMode::Position),
                     );
-                    let target = self.program[self.position + 3] as usize;
+                    let target = self.get_address(
+                        self.position + 3,
+                        param_modes.get(2).unwrap_or(&ParameterMode::Position),
+                    )?;
                     self.put(target, a * b);
                     self.position += 4
                 }
                 (Opcode::Input, param_modes) => {
                     if let Some(input) = inputs.next() {
-                        let a = self.get_value(
+                        let a = self.get_address(
                             self.position + 1,
-                            param_modes.get(0).unwrap_or(&ParameterMode::Immediate),
-                        ) as usize;
-                        dbg!(&input, &param_modes, &a);
+                            param_modes.first().unwrap_or(&ParameterMode::Position),
+                        )?;
                         self.put(a, *input);
                         self.position += 2;
                     } else {
-                        println!("not enough outputs");
-                        return outputs;
+                        return Ok(outputs);
                     }
                 }
                 (Opcode::Output, param_modes) => {
                     let a = self.get_value(
                         self.position + 1,
-                        param_modes.get(0).unwrap_or(&ParameterMode::Position),
+                        param_modes.first().unwrap_or(&ParameterMode::Position),
                     );
                     self.position += 2;
                     outputs.push(a);
@@ -177,7 +275,7 @@ impl Program {
                 (Opcode::JumpIfTrue, param_modes) => {
                     let a = self.get_value(
                         self.position + 1,
-                        param_modes.get(0).unwrap_or(&ParameterMode::Position),
+                        param_modes.first().unwrap_or(&ParameterMode::Position),
                     );
                     if a != 0 {
                         let b = self.get_value(
@@ -192,7 +290,7 @@ impl Program {
                 (Opcode::JumpIfFalse, param_modes) => {
                     let a = self.get_value(
                         self.position + 1,
-                        param_modes.get(0).unwrap_or(&ParameterMode::Position),
+                        param_modes.first().unwrap_or(&ParameterMode::Position),
                     );
                     if a == 0 {
                         let b = self.get_value(
@@ -207,81 +305,147 @@ impl Program {
                 (Opcode::LessThan, param_modes) => {
                     let a = self.get_value(
                         self.position + 1,
-                        param_modes.get(0).unwrap_or(&ParameterMode::Position),
+                        param_modes.first().unwrap_or(&ParameterMode::Position),
                     );
                     let b = self.get_value(
                         self.position + 2,
                         param_modes.get(1).unwrap_or(&ParameterMode::Position),
                     );
-                    let target = self.program[self.position + 3] as usize;
+                    let target = self.get_address(
+                        self.position + 3,
+                        param_modes.get(2).unwrap_or(&ParameterMode::Position),
+                    )?;
                     self.put(target, if a < b { 1 } else { 0 });
                     self.position += 4;
                 }
                 (Opcode::Equals, param_modes) => {
                     let a = self.get_value(
                         self.position + 1,
-                        param_modes.get(0).unwrap_or(&ParameterMode::Position),
+                        param_modes.first().unwrap_or(&ParameterMode::Position),
                     );
                     let b = self.get_value(
                         self.position + 2,
                         param_modes.get(1).unwrap_or(&ParameterMode::Position),
                     );
-                    let target = self.program[self.position + 3] as usize;
+                    let target = self.get_address(
+                        self.position + 3,
+                        param_modes.get(2).unwrap_or(&ParameterMode::Position),
+                    )?;
                     self.put(target, if a == b { 1 } else { 0 });
                     self.position += 4;
                 }
                 (Opcode::AdjRelBase, param_modes) => {
                     let a = self.get_value(
                         self.position + 1,
-                        param_modes.get(0).unwrap_or(&ParameterMode::Position),
+                        param_modes.first().unwrap_or(&ParameterMode::Position),
                     );
                     self.relative_base += a;
                     self.position += 2;
                 }
                 (Opcode::Halt, _param_modes) => {
                     self.complete = true;
-                    return outputs;
+                    return Ok(outputs);
                 }
             }
         }
+        Err(IntcodeError::Timeout { steps: max_steps })
+    }
+
+    /// Runs the program with ASCII input, and returns the ASCII output along
+    /// with any values outside of the ASCII range
+    pub fn run_ascii(&mut self, input: &str) -> Result<(String, Vec<i64>), IntcodeError> {
+        let outputs = self.run(&ascii_input(input))?;
+        Ok(ascii_output(&outputs))
+    }
+}
+
+/// The system ID of the ship's air conditioner unit
+const AIR_CONDITIONER: i64 = 1;
+
+/// The system ID of the ship's thermal radiator controller
+const THERMAL_RADIATOR: i64 = 5;
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        let mut program = Program::new(parse_input(input)?);
+        Ok(program.run(&[AIR_CONDITIONER])?.last().cloned().into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        let mut program = Program::new(parse_input(input)?);
+        Ok(program.run(&[THERMAL_RADIATOR])?.last().cloned().into())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::INPUT;
 
     fn test_parse_input() {
-        assert_eq!(parse_input("4,3,2,1,0"), &[4, 3, 2, 1, 0]);
+        assert_eq!(parse_input("4,3,2,1,0").unwrap(), &[4, 3, 2, 1, 0]);
     }
 
     fn my_input() -> Vec<i64> {
-        let input = include_str!("day5.input");
-        parse_input(input)
+        let input = crate::input(5, INPUT);
+        parse_input(input).unwrap()
     }
 
     #[test]
     fn test_parse_instruction() {
         let tests = &[
             (
-                "1002",
+                1002,
                 (
                     Opcode::Mult,
                     vec![ParameterMode::Position, ParameterMode::Immediate],
                 ),
             ),
             (
-                "1101",
+                1101,
                 (
                     Opcode::Add,
                     vec![ParameterMode::Immediate, ParameterMode::Immediate],
                 ),
             ),
-            ("101", (Opcode::Add, vec![ParameterMode::Immediate])),
-            ("1", (Opcode::Add, vec![])),
+            (101, (Opcode::Add, vec![ParameterMode::Immediate])),
+            (1, (Opcode::Add, vec![])),
         ];
         for (input, expected) in tests {
-            assert_eq!(&parse_instruction(input), expected);
+            assert_eq!(parse_instruction(*input, 0).as_ref(), Ok(expected));
+        }
+
+        let tests = &[
+            (
+                77,
+                IntcodeError::Opcode {
+                    position: 3,
+                    instruction: 77,
+                },
+            ),
+            (
+                -101,
+                IntcodeError::Opcode {
+                    position: 3,
+                    instruction: -101,
+                },
+            ),
+            (
+                99999,
+                IntcodeError::ParameterMode {
+                    position: 3,
+                    instruction: 99999,
+                },
+            ),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(parse_instruction(*input, 3).as_ref(), Err(expected));
         }
     }
 
@@ -289,13 +453,98 @@ mod tests {
     fn test_run_program_part_1() {
         let input = my_input();
         let mut program = Program::new(input);
-        assert_eq!(program.run(&[1]).last().cloned(), Some(5346030 as i64));
+        assert_eq!(
+            program.run(&[1]).unwrap().last().cloned(),
+            Some(5346030_i64)
+        );
     }
 
     #[test]
     fn test_run_program_part_2() {
         let input = my_input();
         let mut program = Program::new(input);
-        assert_eq!(program.run(&[5]).last().cloned(), Some(513116 as i64));
+        assert_eq!(program.run(&[5]).unwrap().last().cloned(), Some(513116_i64));
+    }
+
+    #[test]
+    fn test_run_ascii() {
+        // echoes each input value
+        let input = parse_input("3,100,4,100,1105,1,0").unwrap();
+        let mut program = Program::new(input);
+        assert_eq!(program.run_ascii("hi\n"), Ok(("hi\n".to_string(), vec![])));
+        assert!(!program.complete);
+
+        let input = parse_input("104,72,104,105,104,1000,99").unwrap();
+        let mut program = Program::new(input);
+        assert_eq!(program.run_ascii(""), Ok(("Hi".to_string(), vec![1000])));
+        assert!(program.complete);
+    }
+
+    #[test]
+    fn test_run_for() {
+        // loops forever
+        let input = parse_input("1105,1,0").unwrap();
+        let mut program = Program::new(input);
+        assert_eq!(
+            program.run_for(&[], 1000),
+            Err(IntcodeError::Timeout { steps: 1000 })
+        );
+
+        let input = parse_input("104,1,99").unwrap();
+        let mut program = Program::new(input);
+        assert_eq!(program.run_for(&[], 2), Ok(vec![1]));
+    }
+
+    #[test]
+    fn test_invalid_program() {
+        assert_eq!(
+            parse_input(" \n"),
+            Err(ParseError::new("an Intcode program", ""))
+        );
+
+        let tests = &[
+            (
+                "77,0,0,0",
+                IntcodeError::Opcode {
+                    position: 0,
+                    instruction: 77,
+                },
+            ),
+            (
+                "99999,0",
+                IntcodeError::ParameterMode {
+                    position: 0,
+                    instruction: 99999,
+                },
+            ),
+            (
+                "1101,1,1,-3,99",
+                IntcodeError::Address {
+                    position: 0,
+                    address: -3,
+                },
+            ),
+            // jumps past the end of the program
+            (
+                "1105,1,10",
+                IntcodeError::Opcode {
+                    position: 10,
+                    instruction: 0,
+                },
+            ),
+        ];
+        for (input, expected) in tests {
+            let mut program = Program::new(parse_input(input).unwrap());
+            assert_eq!(program.run(&[]).as_ref(), Err(expected));
+        }
+
+        let err = SolveError::from(IntcodeError::Opcode {
+            position: 0,
+            instruction: 77,
+        });
+        assert_eq!(
+            err.to_string(),
+            "Intcode program failed: unknown opcode in instruction 77 at position 0"
+        );
     }
 }
@@ -1,105 +1,243 @@
 // https://adventofcode.com/2019/day/6
 
-use std::collections::{HashMap, HashSet};
+use aoc_common::{
+    parse_lines, parse_with, Answer, Color, Dot, Drawing, ParseError, Parser, Solution, SolveError,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
 
-struct Graph(HashMap<String, HashSet<String>>);
+/// The orbits as a tree, with each object stored at an index. Every object
+/// but the center of mass orbits exactly one parent, and its depth is the
+/// number of objects it orbits directly or indirectly.
+struct Graph {
+    names: Vec<String>,
+    index: HashMap<String, usize>,
+    parents: Vec<Option<usize>>,
+    children: Vec<Vec<usize>>,
+    depths: Vec<usize>,
+}
 
 impl Graph {
-    fn new(edges: &[(String, String)]) -> Self {
-        let mut graph: HashMap<String, HashSet<String>> = HashMap::new();
-        for (n1, n2) in edges {
-            let entry = graph.entry(n1.to_string()).or_insert_with(HashSet::new);
-            (*entry).insert(n2.to_string());
+    /// Builds the graph from orbits, failing if an object orbits more than
+    /// one center, there is more than one center of mass, or the orbits
+    /// form a cycle
+    fn new(edges: &[(String, String)]) -> Result<Self, ParseError> {
+        let mut names: Vec<String> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        let mut id = |name: &str| match index.get(name) {
+            Some(i) => *i,
+            None => {
+                index.insert(name.to_string(), names.len());
+                names.push(name.to_string());
+                names.len() - 1
+            }
+        };
+        let edges: Vec<_> = edges
+            .iter()
+            .map(|(center, object)| (id(center), id(object)))
+            .collect();
+
+        let mut parents = vec![None; names.len()];
+        let mut children = vec![Vec::new(); names.len()];
+        // the line each object's orbit is on
+        let mut lines = vec![0; names.len()];
+        for (i, (center, object)) in edges.into_iter().enumerate() {
+            if parents[object].is_some() {
+                let err = ParseError::new("one center for each object", &names[object]);
+                return Err(err.at_line(i + 1));
+            }
+            parents[object] = Some(center);
+            children[center].push(object);
+            lines[object] = i + 1;
         }
-        Graph(graph)
-    }
 
-    fn nodes(&self) -> HashSet<&str> {
-        self.0.keys().map(|s| s.as_str()).collect()
+        let roots: Vec<_> = (0..names.len()).filter(|i| parents[*i].is_none()).collect();
+        let root = match roots[..] {
+            [] => None,
+            [root] => Some(root),
+            _ => {
+                let found: Vec<_> = roots.iter().map(|i| names[*i].as_str()).collect();
+                return Err(ParseError::new(
+                    "a single center of mass",
+                    &found.join(", "),
+                ));
+            }
+        };
+
+        // anything not reached from the center of mass is part of a cycle
+        let mut depths = vec![None; names.len()];
+        let mut queue: VecDeque<_> = root.map(|root| (root, 0)).into_iter().collect();
+        while let Some((node, depth)) = queue.pop_front() {
+            depths[node] = Some(depth);
+            queue.extend(children[node].iter().map(|child| (*child, depth + 1)));
+        }
+        if let Some(node) = depths.iter().position(Option::is_none) {
+            let err = ParseError::new("orbits without a cycle", &names[node]);
+            return Err(err.at_line(lines[node]));
+        }
+        let depths = depths.into_iter().flatten().collect();
+
+        Ok(Graph {
+            names,
+            index,
+            parents,
+            children,
+            depths,
+        })
     }
 
-    fn children(&self) -> HashSet<&str> {
-        self.0.values().flatten().map(|s| s.as_str()).collect()
+    fn id(&self, name: &str) -> Option<usize> {
+        self.index.get(name).copied()
     }
 
     fn parent(&self, node: &str) -> Option<&str> {
-        self.0
-            .iter()
-            .filter(|(_k, v)| v.contains(node))
-            .map(|(k, _v)| k.as_str())
-            .next()
+        let parent = self.parents[self.id(node)?]?;
+        Some(&self.names[parent])
     }
 
-    fn path(&self, node: &str, path: &mut Vec<String>) {
-        path.push(node.to_string());
-        if let Some(parent) = self.parent(node) {
-            self.path(parent, path);
-        }
+    /// Returns the objects `node` orbits, from its parent out to the center
+    /// of mass
+    fn ancestors(&self, node: &str) -> impl Iterator<Item = &str> {
+        let parent = self.id(node).and_then(|i| self.parents[i]);
+        std::iter::successors(parent, move |i| self.parents[*i])
+            .map(move |i| self.names[i].as_str())
     }
 
-    fn start(&self) -> Option<&str> {
-        let children = self.children();
-        let nodes = self.nodes();
-        match nodes.difference(&children).collect::<Vec<_>>()[..] {
-            [node] => Some(*node),
-            [] => None,
-            _ => panic!("multiple starting nodes found"),
-        }
+    /// Returns the number of objects `node` orbits directly or indirectly
+    fn orbits(&self, node: &str) -> Option<usize> {
+        Some(self.depths[self.id(node)?])
     }
 
-    fn count_orbits_from(&self, start: &str, counts: &mut Vec<usize>) -> usize {
-        if let Some(children) = self.0.get(start) {
-            let count = children.len()
-                + children
-                    .iter()
-                    .map(|c| self.count_orbits_from(&c, counts))
-                    .sum::<usize>();
-            counts.push(count);
-            count
-        } else {
-            0
+    /// Moves the deeper node up until both are at the same depth, then
+    /// moves both up until they meet
+    fn lca(&self, mut a: usize, mut b: usize) -> usize {
+        while self.depths[a] > self.depths[b] {
+            a = self.parents[a].unwrap();
+        }
+        while self.depths[b] > self.depths[a] {
+            b = self.parents[b].unwrap();
         }
+        while a != b {
+            a = self.parents[a].unwrap();
+            b = self.parents[b].unwrap();
+        }
+        a
+    }
+
+    /// Returns the deepest object that both `a` and `b` are or orbit
+    fn lowest_common_ancestor(&self, a: &str, b: &str) -> Option<&str> {
+        let lca = self.lca(self.id(a)?, self.id(b)?);
+        Some(&self.names[lca])
+    }
+
+    /// Returns the number of orbital transfers needed to move from the object
+    /// `from` is orbiting to the object `to` is orbiting
+    fn transfers(&self, from: &str, to: &str) -> Option<usize> {
+        let from = self.parents[self.id(from)?]?;
+        let to = self.parents[self.id(to)?]?;
+        let lca = self.lca(from, to);
+        Some(self.depths[from] + self.depths[to] - 2 * self.depths[lca])
     }
 
+    /// Returns the total number of direct and indirect orbits
     fn count_orbits(&self) -> usize {
-        let mut counts: Vec<usize> = Vec::new();
-        if let Some(start) = self.start() {
-            self.count_orbits_from(&start, &mut counts);
-        }
-        counts.iter().sum::<usize>()
+        self.depths.iter().sum()
     }
 
-    fn print_from(&self, start: &str) {
-        print!("{}", start);
-        if let Some(children) = self.0.get(start) {
-            print!(" ");
-            for child in children {
-                self.print_from(&child);
+    /// Returns the objects from `a` up to the lowest common ancestor and
+    /// back down to `b`
+    fn route(&self, a: &str, b: &str) -> Option<Vec<usize>> {
+        let (a, b) = (self.id(a)?, self.id(b)?);
+        let lca = self.lca(a, b);
+        let climb = |mut node: usize| {
+            let mut nodes = vec![node];
+            while node != lca {
+                node = self.parents[node].unwrap();
+                nodes.push(node);
             }
-        } else {
-            print!(" | ")
-        }
+            nodes
+        };
+        let mut route = climb(a);
+        let mut down = climb(b);
+        down.pop();
+        route.extend(down.into_iter().rev());
+        Some(route)
     }
 
-    fn print(&self) {
-        if let Some(start) = self.start() {
-            self.print_from(&start);
+    /// Draws the orbits with an edge from each center to the objects
+    /// orbiting it. If `highlight` names two objects, the route between
+    /// them is drawn in red.
+    fn dot(&self, highlight: Option<(&str, &str)>) -> Dot {
+        let route: HashSet<_> = highlight
+            .and_then(|(a, b)| self.route(a, b))
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        let color = ROUTE.hex();
+        let style = |on_route: bool| -> Vec<(&str, &str)> {
+            if on_route {
+                vec![("color", &color), ("penwidth", "2")]
+            } else {
+                vec![]
+            }
+        };
+
+        let mut dot = Dot::new("orbits");
+        for (node, name) in self.names.iter().enumerate() {
+            dot.node(name, &style(route.contains(&node)));
         }
+        for (node, parent) in self.parents.iter().enumerate() {
+            if let Some(parent) = parent {
+                // a route through a tree uses every edge between its objects
+                let on_route = route.contains(parent) && route.contains(&node);
+                dot.edge(&self.names[*parent], &self.names[node], &style(on_route));
+            }
+        }
+        dot
+    }
+}
+
+/// The color of the route between two objects in the drawing
+const ROUTE: Color = Color(255, 0, 0);
+
+/// Parses an orbit like "A)B" on each line
+fn parse_input(s: &str) -> Result<Vec<(String, String)>, ParseError> {
+    parse_lines(s, |line| {
+        parse_with(line, |p: &mut Parser| {
+            let center = p.word()?;
+            p.literal(")")?;
+            let object = p.word()?;
+            Ok((center.to_string(), object.to_string()))
+        })
+    })
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        Graph::new(&parse_input(input)?).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(Graph::new(&parse_input(input)?)?.count_orbits().into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(Graph::new(&parse_input(input)?)?
+            .transfers("YOU", "SAN")
+            .into())
+    }
+
+    fn draw(&self, input: &str) -> Result<Option<Drawing>, SolveError> {
+        let graph = Graph::new(&parse_input(input)?)?;
+        Ok(Some(Drawing::Dot(graph.dot(Some(("YOU", "SAN"))))))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    fn parse_input(s: &str) -> Vec<(String, String)> {
-        s.lines()
-            .map(|line| {
-                let parts: Vec<_> = line.split(')').collect();
-                (parts[0].to_string(), parts[1].to_string())
-            })
-            .collect()
-    }
+    use aoc_common::INPUT;
 
     #[test]
     fn test_part_1() {
@@ -114,13 +252,13 @@ D)I
 E)J
 J)K
 K)L"#;
-        let edges = parse_input(input);
-        let graph = Graph::new(&edges);
+        let edges = parse_input(input).unwrap();
+        let graph = Graph::new(&edges).unwrap();
         assert_eq!(graph.count_orbits(), 42);
 
-        let input = include_str!("day6.input");
-        let edges = parse_input(input);
-        let graph = Graph::new(&edges);
+        let input = crate::input(6, INPUT);
+        let edges = parse_input(input).unwrap();
+        let graph = Graph::new(&edges).unwrap();
         assert_eq!(graph.count_orbits(), 194721);
     }
 
@@ -139,30 +277,91 @@ J)K
 K)L
 K)YOU
 I)SAN"#;
-        let edges = parse_input(input);
-        let graph = Graph::new(&edges);
+        let edges = parse_input(input).unwrap();
+        let graph = Graph::new(&edges).unwrap();
         assert_eq!(graph.count_orbits(), 54);
         assert_eq!(graph.parent("SAN"), Some("I"));
         assert_eq!(graph.parent("COM"), None);
-        let mut path1 = Vec::new();
-        graph.path("SAN", &mut path1);
-        dbg!(&path1);
-        let mut path2 = Vec::new();
-        graph.path("YOU", &mut path2);
-        dbg!(&path2);
-        let path1: HashSet<_> = path1.iter().collect();
-        let path2: HashSet<_> = path2.iter().collect();
-        assert_eq!(path1.symmetric_difference(&path2).count() - 2, 4);
-
-        let input = include_str!("day6.input");
-        let edges = parse_input(input);
-        let graph = Graph::new(&edges);
-        let mut path1 = Vec::new();
-        graph.path("SAN", &mut path1);
-        let mut path2 = Vec::new();
-        graph.path("YOU", &mut path2);
-        let path1: HashSet<_> = path1.iter().collect();
-        let path2: HashSet<_> = path2.iter().collect();
-        dbg!(path1.symmetric_difference(&path2).count() - 2, 316);
+        assert_eq!(graph.transfers("YOU", "SAN"), Some(4));
+
+        let input = crate::input(6, INPUT);
+        let edges = parse_input(input).unwrap();
+        let graph = Graph::new(&edges).unwrap();
+        assert_eq!(graph.transfers("YOU", "SAN"), Some(316));
+    }
+
+    #[test]
+    fn test_ancestors() {
+        let input = "COM)B\nB)C\nC)D\nD)E\nE)F\nB)G\nG)H\nD)I\nE)J\nJ)K\nK)L";
+        let graph = Graph::new(&parse_input(input).unwrap()).unwrap();
+        assert_eq!(
+            graph.ancestors("L").collect::<Vec<_>>(),
+            vec!["K", "J", "E", "D", "C", "B", "COM"]
+        );
+        assert_eq!(graph.ancestors("COM").count(), 0);
+        assert_eq!(graph.ancestors("X").count(), 0);
+        assert_eq!(graph.orbits("D"), Some(3));
+        assert_eq!(graph.orbits("COM"), Some(0));
+        assert_eq!(graph.lowest_common_ancestor("L", "I"), Some("D"));
+        assert_eq!(graph.lowest_common_ancestor("H", "F"), Some("B"));
+        assert_eq!(graph.lowest_common_ancestor("E", "L"), Some("E"));
+        assert_eq!(graph.lowest_common_ancestor("E", "X"), None);
+        assert_eq!(graph.transfers("L", "I"), Some(3));
+        assert_eq!(graph.transfers("F", "J"), Some(0));
+        assert_eq!(graph.transfers("COM", "B"), None);
+    }
+
+    #[test]
+    fn test_dot() {
+        let input = "COM)B\nB)C\nB)D\nC)YOU\nD)SAN\nCOM)E";
+        let graph = Graph::new(&parse_input(input).unwrap()).unwrap();
+        let expected = r##"digraph "orbits" {
+  "COM";
+  "B" [color="#ff0000", penwidth="2"];
+  "C" [color="#ff0000", penwidth="2"];
+  "D" [color="#ff0000", penwidth="2"];
+  "YOU" [color="#ff0000", penwidth="2"];
+  "SAN" [color="#ff0000", penwidth="2"];
+  "E";
+  "COM" -> "B";
+  "B" -> "C" [color="#ff0000", penwidth="2"];
+  "B" -> "D" [color="#ff0000", penwidth="2"];
+  "C" -> "YOU" [color="#ff0000", penwidth="2"];
+  "D" -> "SAN" [color="#ff0000", penwidth="2"];
+  "COM" -> "E";
+}
+"##;
+        assert_eq!(graph.dot(None).to_string().lines().count(), 15);
+        assert_eq!(graph.route("YOU", "SAN"), Some(vec![4, 2, 1, 3, 5]));
+        assert_eq!(graph.dot(Some(("YOU", "SAN"))).to_string(), expected);
+    }
+
+    #[test]
+    fn test_invalid_graph() {
+        let graph = |input| Graph::new(&parse_input(input).unwrap()).err();
+        assert_eq!(
+            graph("COM)B\nX)Y"),
+            Some(ParseError::new("a single center of mass", "COM, X"))
+        );
+        assert_eq!(
+            graph("COM)B\nB)C\nC)D\nD)C"),
+            Some(ParseError::new("one center for each object", "C").at_line(4))
+        );
+        assert_eq!(
+            graph("COM)B\nC)D\nD)E\nE)C"),
+            Some(ParseError::new("orbits without a cycle", "C").at_line(4))
+        );
+        assert!(Graph::new(&[]).is_ok());
+    }
+
+    #[test]
+    fn test_parse_input() {
+        let edge = |a: &str, b: &str| (a.to_string(), b.to_string());
+        assert_eq!(
+            parse_input("COM)B\nB)C"),
+            Ok(vec![edge("COM", "B"), edge("B", "C")])
+        );
+        let expected = ParseError::new("\")\"", "-").at_column(2).at_line(2);
+        assert_eq!(parse_input("COM)B\nB-C"), Err(expected));
     }
 }
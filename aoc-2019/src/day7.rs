@@ -1,32 +1,40 @@
 // https://adventofcode.com/2019/day/7
 
 use crate::day5;
+use aoc_common::{Answer, ParseError, Solution, SolveError};
 use itertools::Itertools;
+use std::ops::RangeInclusive;
 
-fn run_program(phases: &[i64], program: &[i64]) -> i64 {
+/// Returns the last signal an amplifier sends, or an error if it sends none
+fn last_signal(outputs: &[i64]) -> Result<i64, SolveError> {
+    outputs
+        .last()
+        .cloned()
+        .ok_or_else(|| SolveError::Runtime("an amplifier sent no signal".to_string()))
+}
+
+fn run_program(phases: &[i64], program: &[i64]) -> Result<i64, SolveError> {
     let mut output = 0;
     for phase in phases {
         let mut program = day5::Program::new(program.to_vec());
-        output = *program.run(&[*phase, output]).last().unwrap();
+        output = last_signal(&program.run(&[*phase, output])?)?;
     }
-    output
+    Ok(output)
 }
 
-fn run_program_recursive(phases: &[i64], program: &[i64]) -> i64 {
+fn run_program_recursive(phases: &[i64], program: &[i64]) -> Result<i64, SolveError> {
     let mut output = 0;
     let mut programs: Vec<_> = (0..phases.len())
         .map(|_| day5::Program::new(program.to_vec()))
         .collect();
     for (i, phase) in phases.iter().enumerate() {
-        let outputs = programs[i].run(&[*phase, output]);
-        output = *outputs.last().unwrap();
+        output = last_signal(&programs[i].run(&[*phase, output])?)?;
     }
     loop {
         for i in 0..programs.len() {
-            let outputs = programs[i].run(&[output]);
-            output = *outputs.last().unwrap();
+            output = last_signal(&programs[i].run(&[output])?)?;
             if programs[i].complete && i == programs.len() - 1 {
-                return output;
+                return Ok(output);
             }
         }
     }
@@ -40,9 +48,39 @@ fn combinations(items: &[i64]) -> Vec<Vec<i64>> {
         .collect::<Vec<_>>()
 }
 
+/// Returns the highest signal sent to the thrusters for any ordering of
+/// `phases`
+fn max_signal(
+    program: &[i64],
+    phases: RangeInclusive<i64>,
+    run: fn(&[i64], &[i64]) -> Result<i64, SolveError>,
+) -> Result<Option<i64>, SolveError> {
+    let signals = combinations(&phases.collect::<Vec<_>>())
+        .into_iter()
+        .map(|phases| run(&phases, program));
+    itertools::process_results(signals, |signals| signals.max())
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        day5::parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(max_signal(&day5::parse_input(input)?, 0..=4, run_program)?.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(max_signal(&day5::parse_input(input)?, 5..=9, run_program_recursive)?.into())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use aoc_common::INPUT;
 
     #[test]
     fn test_part_1() {
@@ -55,17 +93,17 @@ mod test {
         )];
         for (input, expected) in tests {
             let (program, thruster_signal) = input;
-            let program = day5::parse_input(program);
-            let phases = day5::parse_input(thruster_signal);
-            assert_eq!(&run_program(&phases, &program), expected);
+            let program = day5::parse_input(program).unwrap();
+            let phases = day5::parse_input(thruster_signal).unwrap();
+            assert_eq!(run_program(&phases, &program), Ok(*expected));
         }
 
-        let input = include_str!("day7.input").trim_end();
+        let input = crate::input(7, INPUT).trim_end();
         let output = combinations(&(0..=4).collect::<Vec<i64>>())
             .iter()
             .map(|phases| {
-                let program = day5::parse_input(input);
-                run_program(&phases, &program)
+                let program = day5::parse_input(input).unwrap();
+                run_program(phases, &program).unwrap()
             })
             .max();
         assert_eq!(output, Some(929800));
@@ -79,17 +117,17 @@ mod test {
         ];
         for (input, expected) in tests {
             let (program, thruster_signal) = input;
-            let program = day5::parse_input(program);
-            let phases = day5::parse_input(thruster_signal);
-            assert_eq!(&run_program_recursive(&phases, &program), expected);
+            let program = day5::parse_input(program).unwrap();
+            let phases = day5::parse_input(thruster_signal).unwrap();
+            assert_eq!(run_program_recursive(&phases, &program), Ok(*expected));
         }
 
-        let input = include_str!("day7.input").trim_end();
+        let input = crate::input(7, INPUT).trim_end();
         let output = combinations(&(5..=9).collect::<Vec<i64>>())
             .iter()
             .map(|phases| {
-                let program = day5::parse_input(input);
-                run_program_recursive(&phases, &program)
+                let program = day5::parse_input(input).unwrap();
+                run_program_recursive(phases, &program).unwrap()
             })
             .max();
         assert_eq!(output, Some(15432220));
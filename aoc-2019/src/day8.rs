@@ -1,21 +1,26 @@
 // https://adventofcode.com/2019/day/8
 
+use aoc_common::{
+    ocr_str, parse_digits, Answer, Color, Drawing, Grid, ParseError, Solution, SolveError,
+};
 use std::fmt;
 
-type Layer = Vec<u32>;
-
-fn flatten_layers(top: &[u32], bottom: &[u32]) -> Layer {
-    assert_eq!(top.len(), bottom.len());
-    let mut layer = Layer::new();
-    for (p1, p2) in top.iter().zip(bottom.iter()) {
-        let p = if *p1 == 2 { p2 } else { p1 };
-        layer.push(*p);
-    }
-    layer
+type Layer = Grid<u32>;
+
+fn flatten_layers(top: &Layer, bottom: &Layer) -> Layer {
+    assert_eq!(
+        (top.width(), top.height()),
+        (bottom.width(), bottom.height())
+    );
+    let pixels = top
+        .iter()
+        .zip(bottom.iter())
+        .map(|((_, p1), (_, p2))| if *p1 == 2 { *p2 } else { *p1 })
+        .collect();
+    Grid::from_vec(top.width(), pixels)
 }
 
 struct Image {
-    width: usize,
     layers: Vec<Layer>,
 }
 
@@ -23,14 +28,11 @@ impl fmt::Display for Image {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for (i, layer) in self.layers.iter().enumerate() {
             writeln!(f, "layer {}:", i)?;
-            for row in layer.chunks(self.width) {
-                let row: String = row
-                    .iter()
-                    .map(|n| n.to_string())
-                    .map(|s| if s == "0" { " ".to_string() } else { s })
-                    .collect();
-                writeln!(f, "{}", row)?;
-            }
+            let layer = layer.render(|n| match n {
+                0 => ' ',
+                n => std::char::from_digit(*n, 10).unwrap_or('?'),
+            });
+            writeln!(f, "{}", layer)?;
         }
         Ok(())
     }
@@ -38,14 +40,17 @@ impl fmt::Display for Image {
 
 impl Image {
     fn new(width: usize, height: usize, data: &[u32]) -> Self {
-        let layers: Vec<Layer> = data.chunks(width * height).map(|c| c.to_vec()).collect();
-        Image { width, layers }
+        let layers: Vec<Layer> = data
+            .chunks(width * height)
+            .map(|c| Grid::from_vec(width, c.to_vec()))
+            .collect();
+        Image { layers }
     }
 
     fn layer_with_fewest(&self, n: u32) -> Option<&Layer> {
         self.layers
             .iter()
-            .map(|layer| (layer, layer.iter().filter(|i| i == &&n).count()))
+            .map(|layer| (layer, layer.iter().filter(|(_, i)| **i == n).count()))
             // error: cannot infer an appropriate lifetime for pattern due to conflicting requirements
             // .max_by_key(|(_layer, count)| count)
             .min_by_key(|layer_count| layer_count.1)
@@ -54,74 +59,115 @@ impl Image {
 
     fn checksum(&self) -> Option<usize> {
         if let Some(layer) = self.layer_with_fewest(0) {
-            println!(
-                "fewest: {}",
-                layer.iter().map(|n| n.to_string()).collect::<String>()
-            );
-            let ones = layer.iter().filter(|n| **n == 1).count();
-            let twos = layer.iter().filter(|n| **n == 2).count();
+            let ones = layer.iter().filter(|(_, n)| **n == 1).count();
+            let twos = layer.iter().filter(|(_, n)| **n == 2).count();
             Some(ones * twos)
         } else {
             None
         }
     }
 
+    /// Renders the first layer, with white pixels as `#` and black pixels
+    /// as `.`
+    fn render(&self) -> String {
+        self.layers.first().map_or_else(String::new, |layer| {
+            layer.render(|n| if *n == 1 { '#' } else { '.' })
+        })
+    }
+
     fn flatten(&self) -> Self {
-        let width = self.width;
-        let len = self.layers.first().map_or(0, |layer| layer.len());
-        let layer = self
-            .layers
-            .iter()
-            .fold(vec![2; len], |acc, layer| flatten_layers(&acc, layer));
-        let layers = vec![layer];
-        Image { width, layers }
+        let layers = match self.layers.first() {
+            Some(first) => {
+                let transparent = Grid::new(first.width(), first.height(), 2);
+                let layer = self
+                    .layers
+                    .iter()
+                    .fold(transparent, |acc, layer| flatten_layers(&acc, layer));
+                vec![layer]
+            }
+            None => vec![],
+        };
+        Image { layers }
     }
 }
 
-fn parse_input(s: &str) -> Vec<u32> {
-    s.trim_end()
-        .chars()
-        .map(|c| c.to_digit(10).expect("invalid digit"))
-        .collect()
+fn parse_input(s: &str) -> Result<Vec<u32>, ParseError> {
+    parse_digits(s)
+}
+
+const WIDTH: usize = 25;
+const HEIGHT: usize = 6;
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        Ok(Image::new(WIDTH, HEIGHT, &parse_input(input)?)
+            .checksum()
+            .into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        let image = Image::new(WIDTH, HEIGHT, &parse_input(input)?)
+            .flatten()
+            .render();
+        Ok(ocr_str(&image).map_or_else(|_| Answer::lines(&image), Answer::from))
+    }
+
+    fn draw(&self, input: &str) -> Result<Option<Drawing>, SolveError> {
+        let image = Image::new(WIDTH, HEIGHT, &parse_input(input)?).flatten();
+        Ok(image.layers.first().map(|layer| {
+            Drawing::Image(layer.map(|n| match n {
+                0 => Color::BLACK,
+                1 => Color::WHITE,
+                _ => Color::gray(128),
+            }))
+        }))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::INPUT;
 
     fn test_parse_input() {
         let input = "123456789012";
         let expected = &[1, 2, 3, 4, 5, 6, 8, 9, 0, 1, 2];
-        assert_eq!(parse_input(input), expected);
+        assert_eq!(parse_input(input).unwrap(), expected);
     }
 
     #[test]
     fn test_image() {
-        let input = parse_input("123456789012");
+        let input = parse_input("123456789012").unwrap();
         Image::new(3, 2, &input);
     }
 
     #[test]
     fn test_checksum() {
-        let input = parse_input("123456789012");
+        let input = parse_input("123456789012").unwrap();
         let image = Image::new(3, 2, &input);
         assert_eq!(image.checksum().unwrap(), 1);
 
-        let input = parse_input(include_str!("day8.input"));
+        let input = parse_input(crate::input(8, INPUT)).unwrap();
         let image = Image::new(25, 6, &input);
         assert_eq!(image.checksum().unwrap(), 1548)
     }
 
     #[test]
     fn test_flatten() {
-        let input = parse_input("0222112222120000");
+        let input = parse_input("0222112222120000").unwrap();
         let image = Image::new(2, 2, &input);
         let image = image.flatten();
-        println!("{}", image);
+        assert_eq!(image.render(), ".#\n#.");
 
-        let input = parse_input(include_str!("day8.input"));
+        let input = parse_input(crate::input(8, INPUT)).unwrap();
         let image = Image::new(25, 6, &input);
         let image = image.flatten();
-        println!("{}", image);
+        assert_eq!(ocr_str(&image.render()), Ok("CEKUA".to_string()));
     }
 }
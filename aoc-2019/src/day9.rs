@@ -1,32 +1,61 @@
 // https://adventofcode.com/2019/day/09
 
+use crate::day5;
+use aoc_common::{Answer, ParseError, Solution, SolveError};
+
+/// Runs the BOOST program in test mode
+const TEST_MODE: i64 = 1;
+
+/// Runs the BOOST program in sensor boost mode
+const SENSOR_BOOST_MODE: i64 = 2;
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        day5::parse_input(input).map(|_| ())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        let mut program = day5::Program::new(day5::parse_input(input)?);
+        Ok(program.run(&[TEST_MODE])?.last().cloned().into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        let mut program = day5::Program::new(day5::parse_input(input)?);
+        Ok(program.run(&[SENSOR_BOOST_MODE])?.last().cloned().into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::day5;
+    use super::*;
+    use aoc_common::INPUT;
 
     #[test]
     fn test_examples() {
         // should output the large number in the middle
-        let input = day5::parse_input("104,1125899906842624,99");
+        let input = day5::parse_input("104,1125899906842624,99").unwrap();
         let mut program = day5::Program::new(input.to_vec());
-        assert_eq!(program.run(&[]), &[input[1]]);
+        assert_eq!(program.run(&[]).unwrap(), &[input[1]]);
 
         // should output a 16-digit number
-        let input = day5::parse_input("1102,34915192,34915192,7,4,7,99,0");
+        let input = day5::parse_input("1102,34915192,34915192,7,4,7,99,0").unwrap();
         let mut program = day5::Program::new(input.to_vec());
-        assert_eq!(program.run(&[])[0].to_string().chars().count(), 16);
+        assert_eq!(program.run(&[]).unwrap()[0].to_string().chars().count(), 16);
 
         // takes no input and produces a copy of itself as output
-        let input = day5::parse_input("109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99");
+        let input =
+            day5::parse_input("109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99").unwrap();
         let mut program = day5::Program::new(input.to_vec());
-        assert_eq!(program.run(&[]), input);
+        assert_eq!(program.run(&[]).unwrap(), input);
     }
 
     #[test]
     fn test_my_input() {
-        let input = day5::parse_input(include_str!("day9.input"));
+        let input = day5::parse_input(crate::input(9, INPUT)).unwrap();
         let mut program = day5::Program::new(input.to_vec());
         // Answer is not 203
-        assert_eq!(program.run(&[1]), &[]);
+        assert_eq!(program.run(&[1]).unwrap(), &[3546494377]);
     }
 }
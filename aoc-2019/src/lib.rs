@@ -11,8 +11,16 @@ mod day16;
 #[allow(dead_code)]
 mod day18;
 #[allow(dead_code)]
+mod day19;
+#[allow(dead_code)]
 mod day2;
 #[allow(dead_code)]
+mod day21;
+#[allow(dead_code)]
+mod day23;
+#[allow(dead_code)]
+mod day25;
+#[allow(dead_code)]
 mod day3;
 #[allow(dead_code)]
 mod day4;
@@ -26,3 +34,100 @@ mod day7;
 mod day8;
 #[allow(dead_code)]
 mod day9;
+
+use aoc_common::{Registry, INPUT};
+
+pub const YEAR: u32 = 2019;
+
+/// Adds the solution for each day to the registry
+pub fn register(registry: &mut Registry) {
+    registry.register(YEAR, 1, day1::Puzzle);
+    registry.register(YEAR, 2, day2::Puzzle);
+    registry.register(YEAR, 3, day3::Puzzle);
+    registry.register(YEAR, 4, day4::Puzzle);
+    registry.register(YEAR, 5, day5::Puzzle);
+    registry.register(YEAR, 6, day6::Puzzle);
+    registry.register(YEAR, 7, day7::Puzzle);
+    registry.register(YEAR, 8, day8::Puzzle);
+    registry.register(YEAR, 9, day9::Puzzle);
+    registry.register(YEAR, 10, day10::Puzzle);
+    registry.register(YEAR, 12, day12::Puzzle);
+    registry.register(YEAR, 16, day16::Puzzle);
+    registry.register(YEAR, 19, day19::Puzzle);
+    registry.register(YEAR, 21, day21::Puzzle);
+    registry.register(YEAR, 23, day23::Puzzle);
+    registry.register(YEAR, 25, day25::Puzzle);
+
+    registry.add_input(YEAR, 1, INPUT, include_str!("day1.input"));
+    registry.add_input(YEAR, 2, INPUT, include_str!("day2.input"));
+    registry.add_input(YEAR, 3, INPUT, include_str!("day3.input"));
+    registry.add_input(YEAR, 4, INPUT, include_str!("day4.input"));
+    registry.add_input(YEAR, 5, INPUT, include_str!("day5.input"));
+    registry.add_input(YEAR, 6, INPUT, include_str!("day6.input"));
+    registry.add_input(YEAR, 7, INPUT, include_str!("day7.input"));
+    registry.add_input(YEAR, 8, INPUT, include_str!("day8.input"));
+    registry.add_input(YEAR, 9, INPUT, include_str!("day9.input"));
+    registry.add_input(YEAR, 10, INPUT, include_str!("day10.input"));
+    registry.add_input(YEAR, 12, INPUT, include_str!("day12.input"));
+    registry.add_input(YEAR, 14, INPUT, include_str!("day14.input"));
+    registry.add_input(YEAR, 16, INPUT, include_str!("day16.input"));
+    registry.add_input(YEAR, 18, INPUT, include_str!("day18.input"));
+}
+
+#[cfg(test)]
+thread_local! {
+    /// The registry the tests read embedded inputs from
+    static REGISTRY: Registry = {
+        let mut registry = Registry::new();
+        register(&mut registry);
+        registry
+    };
+}
+
+/// Returns a named embedded input for a day. The tests check the answers
+/// for these inputs, so they never read the input cache.
+#[cfg(test)]
+fn input(day: u32, name: &str) -> &'static str {
+    REGISTRY
+        .with(|registry| registry.input(YEAR, day, name))
+        .unwrap_or_else(|| panic!("no {} for {} day {}", name, YEAR, day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::Answer;
+
+    #[test]
+    fn test_register() {
+        let mut registry = Registry::new();
+        register(&mut registry);
+        let days: Vec<_> = registry.days().map(|(_, day)| day).collect();
+        assert_eq!(
+            days,
+            &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 12, 16, 19, 21, 23, 25]
+        );
+
+        let tests = &[
+            (1, (3373568, 5057481)),
+            (2, (3760627, 7195)),
+            (4, (921, 603)),
+            (5, (5346030, 513116)),
+            (6, (194721, 316)),
+            (7, (929800, 15432220)),
+            (10, (227, 604)),
+            (12, (5937, 376203951569712)),
+        ];
+        for (day, (part1, part2)) in tests {
+            let solution = registry.get(YEAR, *day).unwrap();
+            let input = registry.input(YEAR, *day, INPUT).unwrap();
+            assert_eq!(solution.part1(input), Ok(Answer::Number(*part1)));
+            assert_eq!(solution.part2(input), Ok(Answer::Number(*part2)));
+        }
+
+        let solution = registry.get(YEAR, 8).unwrap();
+        let input = registry.input(YEAR, 8, INPUT).unwrap();
+        assert_eq!(solution.part1(input), Ok(Answer::Number(1548)));
+        assert_eq!(solution.part2(input), Ok(Answer::from("CEKUA")));
+    }
+}
@@ -0,0 +1,286 @@
+use crate::{parse_lines, parse_with, Answer, ParseError, Parser, Registry, SolveError};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{Duration, Instant};
+use std::{fmt, io};
+
+/// Returns an answer as it is written in an answers file, on one line with
+/// any line breaks written as `\n`
+pub fn encode_answer(answer: &Answer) -> String {
+    answer.to_string().replace('\n', "\\n")
+}
+
+/// The known answers for each year, day, part and named input. An answers
+/// file has one answer per line, like `2018 7 1 input CABDFE`, with the
+/// answer written by `encode_answer`. A line starting with `slow` marks a
+/// part which takes too long to check every time. Blank lines and lines
+/// starting with `#` are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    answers: BTreeMap<(u32, u32, u32, String), String>,
+    slow: BTreeSet<(u32, u32, u32, String)>,
+}
+
+impl Answers {
+    pub fn new() -> Self {
+        let answers = BTreeMap::new();
+        let slow = BTreeSet::new();
+        Answers { answers, slow }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        let mut answers = Answers::new();
+        let lines = parse_lines(s, |line| {
+            if line.trim().is_empty() || line.starts_with('#') {
+                return Ok(None);
+            }
+            parse_with(line, |p: &mut Parser| {
+                let slow = p.try_literal("slow ");
+                let year = p.unsigned()?;
+                p.literal(" ")?;
+                let day = p.unsigned()?;
+                p.literal(" ")?;
+                let part =
+                    p.satisfy("a part", |c| c.to_digit(10).filter(|n| (1..=2).contains(n)))?;
+                p.literal(" ")?;
+                let input = p.word()?.to_string();
+                p.literal(" ")?;
+                let answer = p.take_while(|_| true);
+                match answer {
+                    "" => Err(p.error("an answer")),
+                    answer => Ok(Some(((year, day, part, input), answer.to_string(), slow))),
+                }
+            })
+        })?;
+        for (key, answer, slow) in lines.into_iter().flatten() {
+            if slow {
+                answers.slow.insert(key.clone());
+            }
+            answers.answers.insert(key, answer);
+        }
+        Ok(answers)
+    }
+
+    /// Adds the answer for a part, replacing any existing answer
+    pub fn insert(&mut self, year: u32, day: u32, part: u32, input: &str, answer: &Answer) {
+        let key = (year, day, part, input.to_string());
+        self.answers.insert(key, encode_answer(answer));
+    }
+
+    /// Returns the encoded answer for a part
+    pub fn get(&self, year: u32, day: u32, part: u32, input: &str) -> Option<&str> {
+        let key = (year, day, part, input.to_string());
+        self.answers.get(&key).map(|answer| answer.as_str())
+    }
+
+    /// Returns true if the part is marked as slow to check
+    pub fn is_slow(&self, year: u32, day: u32, part: u32, input: &str) -> bool {
+        let key = (year, day, part, input.to_string());
+        self.slow.contains(&key)
+    }
+
+    /// Returns each (year, day, part, input) with a known answer, in order
+    pub fn keys(&self) -> impl Iterator<Item = (u32, u32, u32, &str)> + '_ {
+        self.answers
+            .keys()
+            .map(|(year, day, part, input)| (*year, *day, *part, input.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.answers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.answers.is_empty()
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, answer) in &self.answers {
+            if self.slow.contains(key) {
+                write!(f, "slow ")?;
+            }
+            let (year, day, part, input) = key;
+            writeln!(f, "{} {} {} {} {}", year, day, part, input, answer)?;
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of checking one part against the known answers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    /// The answer is wrong, or the part failed
+    Fail,
+    /// There is no known answer to check against
+    Unknown,
+}
+
+/// The result of running one part of a solution on a named input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    pub input: String,
+    pub answer: Result<Answer, SolveError>,
+    pub expected: Option<String>,
+    pub elapsed: Duration,
+}
+
+impl Check {
+    pub fn status(&self) -> Status {
+        match (&self.answer, &self.expected) {
+            (Err(_), _) => Status::Fail,
+            (Ok(_), None) => Status::Unknown,
+            (Ok(answer), Some(expected)) if encode_answer(answer) == *expected => Status::Pass,
+            (Ok(_), Some(_)) => Status::Fail,
+        }
+    }
+}
+
+/// Runs one part of the solution for a day on a named input, and compares
+/// the answer with the known answer. Returns None if the day has no
+/// solution or no input with that name.
+pub fn check(
+    registry: &Registry,
+    answers: &Answers,
+    (year, day): (u32, u32),
+    input: &str,
+    part: u32,
+) -> io::Result<Option<Check>> {
+    let (solution, text) = match (registry.get(year, day), registry.load(year, day, input)?) {
+        (Some(solution), Some(text)) => (solution, text),
+        _ => return Ok(None),
+    };
+
+    let start = Instant::now();
+    let answer = if part == 1 {
+        solution.part1(&text)
+    } else {
+        solution.part2(&text)
+    };
+    let elapsed = start.elapsed();
+
+    let expected = answers.get(year, day, part, input).map(str::to_string);
+    Ok(Some(Check {
+        year,
+        day,
+        part,
+        input: input.to_string(),
+        answer,
+        expected,
+        elapsed,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_number, Solution, EXAMPLE, INPUT};
+
+    const ANSWERS: &str = "\
+# year day part input answer
+2019 1 1 input 6
+2019 1 2 input #.\\n.#
+
+2019 1 1 example 10
+slow 2019 2 1 input 7
+";
+
+    struct Sum;
+
+    impl Solution for Sum {
+        fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+            let nums = input
+                .split_whitespace()
+                .map(parse_number)
+                .collect::<Result<Vec<i64>, _>>()?;
+            Ok(nums.iter().sum::<i64>().into())
+        }
+
+        fn part2(&self, _input: &str) -> Result<Answer, SolveError> {
+            Ok(Answer::lines("#.\n.#"))
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        assert_eq!(answers.len(), 4);
+        assert_eq!(answers.get(2019, 1, 1, INPUT), Some("6"));
+        assert_eq!(answers.get(2019, 2, 1, INPUT), Some("7"));
+        assert!(answers.is_slow(2019, 2, 1, INPUT));
+        assert!(!answers.is_slow(2019, 1, 1, INPUT));
+        assert_eq!(answers.get(2019, 1, 2, INPUT), Some("#.\\n.#"));
+        assert_eq!(answers.get(2019, 1, 2, EXAMPLE), None);
+        assert_eq!(
+            answers.keys().collect::<Vec<_>>(),
+            vec![
+                (2019, 1, 1, EXAMPLE),
+                (2019, 1, 1, INPUT),
+                (2019, 1, 2, INPUT),
+                (2019, 2, 1, INPUT)
+            ]
+        );
+        assert_eq!(Answers::parse(&answers.to_string()), Ok(answers));
+
+        let tests = &[
+            (
+                "2019 1 3 input 6",
+                ParseError::new("a part", "3").at_column(8),
+            ),
+            ("2019 1 1 input", ParseError::new("\" \"", "").at_column(15)),
+            (
+                "2019 1 1 input ",
+                ParseError::new("an answer", "").at_column(16),
+            ),
+            (
+                "slow 2019 1 1 input",
+                ParseError::new("\" \"", "").at_column(20),
+            ),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(Answers::parse(input), Err(expected.clone().at_line(1)));
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        let mut registry = Registry::new();
+        registry.register(2019, 1, Sum);
+        registry.add_input(2019, 1, INPUT, "1 2 3");
+        registry.add_input(2019, 1, EXAMPLE, "1 2 x");
+        registry.add_input(2019, 1, "example2", "4");
+        let mut answers = Answers::parse(ANSWERS).unwrap();
+
+        let tests = &[
+            ((INPUT, 1), Status::Pass),
+            ((INPUT, 2), Status::Pass),
+            ((EXAMPLE, 1), Status::Fail),
+            (("example2", 1), Status::Unknown),
+        ];
+        for ((input, part), expected) in tests {
+            let result = check(&registry, &answers, (2019, 1), input, *part)
+                .unwrap()
+                .unwrap();
+            assert_eq!(result.status(), *expected);
+        }
+
+        answers.insert(2019, 1, 1, INPUT, &Answer::Number(7));
+        let result = check(&registry, &answers, (2019, 1), INPUT, 1)
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.status(), Status::Fail);
+        assert_eq!(result.answer, Ok(Answer::Number(6)));
+        assert_eq!(result.expected.as_deref(), Some("7"));
+
+        assert!(check(&registry, &answers, (2019, 2), INPUT, 1)
+            .unwrap()
+            .is_none());
+        assert!(check(&registry, &answers, (2019, 1), "example3", 1)
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,215 @@
+use crate::{Solution, SolveError};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// The times taken by repeated runs of one step of a solution
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Timing {
+    pub runs: Vec<Duration>,
+}
+
+impl Timing {
+    /// Runs `f` the given number of times, and records the time each run
+    /// takes. Stops at the first error.
+    pub fn measure<T, E, F>(runs: usize, mut f: F) -> Result<Self, SolveError>
+    where
+        E: Into<SolveError>,
+        F: FnMut() -> Result<T, E>,
+    {
+        let mut timing = Timing::default();
+        for _ in 0..runs {
+            let start = Instant::now();
+            f().map_err(Into::into)?;
+            timing.runs.push(start.elapsed());
+        }
+        Ok(timing)
+    }
+
+    pub fn mean(&self) -> Duration {
+        match self.runs.len() {
+            0 => Duration::default(),
+            n => self.runs.iter().sum::<Duration>() / n as u32,
+        }
+    }
+
+    pub fn min(&self) -> Duration {
+        self.runs.iter().min().cloned().unwrap_or_default()
+    }
+}
+
+/// The timings for parsing the input of a day and solving the selected
+/// parts
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Benchmark {
+    pub parse: Timing,
+    pub parts: Vec<(u32, Timing)>,
+}
+
+/// Times parsing the input and solving each of `parts` over repeated runs
+pub fn bench(
+    solution: &dyn Solution,
+    input: &str,
+    parts: &[u32],
+    runs: usize,
+) -> Result<Benchmark, SolveError> {
+    let parse = Timing::measure(runs, || solution.parse(input))?;
+    let parts = parts
+        .iter()
+        .map(|&part| {
+            let timing = Timing::measure(runs, || {
+                if part == 1 {
+                    solution.part1(input)
+                } else {
+                    solution.part2(input)
+                }
+            })?;
+            Ok((part, timing))
+        })
+        .collect::<Result<_, SolveError>>()?;
+    Ok(Benchmark { parse, parts })
+}
+
+/// A benchmark report for a number of days. It is written as a JSON array
+/// with one object per day, holding the mean and minimum time in
+/// nanoseconds for parsing and for each part.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    entries: Vec<(u32, u32, String, Benchmark)>,
+}
+
+impl Report {
+    pub fn new() -> Self {
+        let entries = Vec::new();
+        Report { entries }
+    }
+
+    pub fn add(&mut self, year: u32, day: u32, input: &str, benchmark: Benchmark) {
+        self.entries.push((year, day, input.to_string(), benchmark));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+fn write_timing(f: &mut fmt::Formatter<'_>, name: &str, timing: &Timing) -> fmt::Result {
+    write!(
+        f,
+        ", \"{}\": {{\"mean_ns\": {}, \"min_ns\": {}}}",
+        name,
+        timing.mean().as_nanos(),
+        timing.min().as_nanos()
+    )
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "[")?;
+        for (i, (year, day, input, benchmark)) in self.entries.iter().enumerate() {
+            let input = input.replace('\\', "\\\\").replace('"', "\\\"");
+            write!(
+                f,
+                "  {{\"year\": {}, \"day\": {}, \"input\": \"{}\", \"runs\": {}",
+                year,
+                day,
+                input,
+                benchmark.parse.runs.len()
+            )?;
+            write_timing(f, "parse", &benchmark.parse)?;
+            for (part, timing) in &benchmark.parts {
+                write_timing(f, &format!("part{}", part), timing)?;
+            }
+            let separator = if i + 1 < self.entries.len() { "," } else { "" };
+            writeln!(f, "}}{}", separator)?;
+        }
+        writeln!(f, "]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_number, Answer, ParseError, INPUT};
+
+    struct Sum;
+
+    impl Solution for Sum {
+        fn parse(&self, input: &str) -> Result<(), ParseError> {
+            for n in input.split_whitespace() {
+                parse_number::<i64>(n)?;
+            }
+            Ok(())
+        }
+
+        fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+            let nums = input
+                .split_whitespace()
+                .map(parse_number)
+                .collect::<Result<Vec<i64>, _>>()?;
+            Ok(nums.iter().sum::<i64>().into())
+        }
+    }
+
+    fn millis(runs: &[u64]) -> Timing {
+        let runs = runs.iter().map(|&ms| Duration::from_millis(ms)).collect();
+        Timing { runs }
+    }
+
+    #[test]
+    fn test_timing() {
+        let timing = millis(&[3, 1, 5]);
+        assert_eq!(timing.mean(), Duration::from_millis(3));
+        assert_eq!(timing.min(), Duration::from_millis(1));
+        assert_eq!(Timing::default().mean(), Duration::default());
+
+        let mut calls = 0;
+        let timing = Timing::measure(4, || {
+            calls += 1;
+            Ok::<_, SolveError>(())
+        })
+        .unwrap();
+        assert_eq!((calls, timing.runs.len()), (4, 4));
+    }
+
+    #[test]
+    fn test_bench() {
+        let benchmark = bench(&Sum, "1 2 3", &[1, 2], 3).unwrap();
+        assert_eq!(benchmark.parse.runs.len(), 3);
+        assert_eq!(
+            benchmark
+                .parts
+                .iter()
+                .map(|(part, timing)| (*part, timing.runs.len()))
+                .collect::<Vec<_>>(),
+            vec![(1, 3), (2, 3)]
+        );
+        assert_eq!(
+            bench(&Sum, "1 x 3", &[1], 3),
+            Err(ParseError::new("a number", "x").into())
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let mut report = Report::new();
+        assert!(report.is_empty());
+        let benchmark = Benchmark {
+            parse: millis(&[1, 1]),
+            parts: vec![(1, millis(&[2, 4])), (2, millis(&[6, 8]))],
+        };
+        report.add(2019, 1, INPUT, benchmark);
+        report.add(2019, 2, "a \"quoted\" name", Benchmark::default());
+        assert_eq!(report.len(), 2);
+
+        let expected = r#"[
+  {"year": 2019, "day": 1, "input": "input", "runs": 2, "parse": {"mean_ns": 1000000, "min_ns": 1000000}, "part1": {"mean_ns": 3000000, "min_ns": 2000000}, "part2": {"mean_ns": 7000000, "min_ns": 6000000}},
+  {"year": 2019, "day": 2, "input": "a \"quoted\" name", "runs": 0, "parse": {"mean_ns": 0, "min_ns": 0}}
+]
+"#;
+        assert_eq!(report.to_string(), expected);
+    }
+}
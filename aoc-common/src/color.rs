@@ -0,0 +1,61 @@
+/// A color, as red, green and blue levels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color(pub u8, pub u8, pub u8);
+
+/// Colors that are easy to tell apart, used by `Color::indexed`
+const PALETTE: &[Color] = &[
+    Color(230, 25, 75),
+    Color(60, 180, 75),
+    Color(255, 225, 25),
+    Color(0, 130, 200),
+    Color(245, 130, 48),
+    Color(145, 30, 180),
+    Color(70, 240, 240),
+    Color(240, 50, 230),
+    Color(210, 245, 60),
+    Color(250, 190, 212),
+    Color(0, 128, 128),
+    Color(170, 110, 40),
+];
+
+impl Color {
+    pub const BLACK: Color = Color(0, 0, 0);
+    pub const WHITE: Color = Color(255, 255, 255);
+
+    pub fn gray(level: u8) -> Self {
+        Color(level, level, level)
+    }
+
+    /// Returns the `i`th of a set of distinct colors, for telling regions
+    /// apart. The colors repeat after a dozen.
+    pub fn indexed(i: usize) -> Self {
+        PALETTE[i % PALETTE.len()]
+    }
+
+    /// Returns the brightness of the color, from 0 for black to 255 for
+    /// white
+    pub fn luma(&self) -> u8 {
+        let Color(r, g, b) = *self;
+        ((299 * r as u32 + 587 * g as u32 + 114 * b as u32) / 1000) as u8
+    }
+
+    /// Returns the color as written in SVG and HTML, like `#ff8000`
+    pub fn hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color() {
+        assert_eq!(Color::BLACK.luma(), 0);
+        assert_eq!(Color::WHITE.luma(), 255);
+        assert_eq!(Color::gray(100).luma(), 100);
+        assert_eq!(Color::indexed(1), Color::indexed(1 + PALETTE.len()));
+        assert_ne!(Color::indexed(0), Color::indexed(1));
+        assert_eq!(Color(255, 128, 0).hex(), "#ff8000");
+    }
+}
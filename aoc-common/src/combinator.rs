@@ -0,0 +1,319 @@
+use crate::ParseError;
+use std::str::FromStr;
+
+/// A cursor over a line of puzzle input. Each method parses one piece of the
+/// format from the front of the remaining text, and fails with the column
+/// where the input stopped matching. Methods which take a closure combine
+/// smaller parsers into larger ones.
+#[derive(Debug, Clone)]
+pub struct Parser<'a> {
+    input: &'a str,
+    rest: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(input: &'a str) -> Self {
+        let rest = input;
+        Parser { input, rest }
+    }
+
+    /// Returns the text which has not been parsed yet
+    pub fn rest(&self) -> &'a str {
+        self.rest
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rest.is_empty()
+    }
+
+    /// Returns the column of the next character, starting at 1
+    pub fn column(&self) -> usize {
+        let parsed = &self.input[..self.input.len() - self.rest.len()];
+        parsed.chars().count() + 1
+    }
+
+    /// Returns an error at the current column. The text found is the next
+    /// word, or the next character if it is not part of a word, along with
+    /// any whitespace before it.
+    pub fn error(&self, expected: &str) -> ParseError {
+        let mut found = self.clone();
+        found.whitespace();
+        if found.take_while(char::is_alphanumeric).is_empty() {
+            let len = found.rest.chars().next().map_or(0, char::len_utf8);
+            found.advance(len);
+        }
+        let len = self.rest.len() - found.rest.len();
+        ParseError::new(expected, &self.rest[..len]).at_column(self.column())
+    }
+
+    fn advance(&mut self, len: usize) -> &'a str {
+        let (taken, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        taken
+    }
+
+    /// Takes the longest prefix whose characters all match `f`, which may
+    /// be empty
+    pub fn take_while<F: Fn(char) -> bool>(&mut self, f: F) -> &'a str {
+        let len = self.rest.find(|c| !f(c)).unwrap_or(self.rest.len());
+        self.advance(len)
+    }
+
+    /// Skips any whitespace
+    pub fn whitespace(&mut self) {
+        self.take_while(char::is_whitespace);
+    }
+
+    /// Parses exactly the text `lit`
+    pub fn literal(&mut self, lit: &str) -> Result<(), ParseError> {
+        if self.try_literal(lit) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("{:?}", lit)))
+        }
+    }
+
+    /// Parses the text `lit` if it comes next, and returns whether it did
+    pub fn try_literal(&mut self, lit: &str) -> bool {
+        if self.rest.starts_with(lit) {
+            self.advance(lit.len());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Parses the next character if `f` maps it to a value
+    pub fn satisfy<T, F>(&mut self, expected: &str, f: F) -> Result<T, ParseError>
+    where
+        F: FnOnce(char) -> Option<T>,
+    {
+        let c = self.rest.chars().next();
+        match c.and_then(f) {
+            Some(value) => {
+                self.advance(c.map_or(0, char::len_utf8));
+                Ok(value)
+            }
+            None => Err(self.error(expected)),
+        }
+    }
+
+    /// Parses a single letter
+    pub fn letter(&mut self) -> Result<char, ParseError> {
+        self.satisfy("a letter", |c| Some(c).filter(|c| c.is_alphabetic()))
+    }
+
+    /// Parses a word of one or more letters or digits
+    pub fn word(&mut self) -> Result<&'a str, ParseError> {
+        match self.take_while(char::is_alphanumeric) {
+            "" => Err(self.error("a word")),
+            word => Ok(word),
+        }
+    }
+
+    /// Parses a number made of one or more digits
+    pub fn unsigned<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        let start = self.clone();
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        digits.parse().map_err(|_| {
+            *self = start;
+            self.error("a number")
+        })
+    }
+
+    /// Parses a number made of one or more digits, with an optional sign
+    pub fn signed<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        let start = self.clone();
+        let sign = self.take_while(|c| c == '+' || c == '-');
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        let number = &start.rest[..sign.len() + digits.len()];
+        match (sign.len(), number.parse()) {
+            (0..=1, Ok(number)) if !digits.is_empty() => Ok(number),
+            _ => {
+                *self = start;
+                Err(self.error("a number"))
+            }
+        }
+    }
+
+    /// Runs `f`, and moves back to where it started if it fails
+    pub fn optional<T, F>(&mut self, f: F) -> Option<T>
+    where
+        F: FnOnce(&mut Self) -> Result<T, ParseError>,
+    {
+        let start = self.clone();
+        let result = f(self).ok();
+        if result.is_none() {
+            *self = start;
+        }
+        result
+    }
+
+    /// Runs `f` as many times as it succeeds, which may be none. It stops
+    /// at an item that parses no text, which would otherwise repeat forever.
+    pub fn many<T, F>(&mut self, mut f: F) -> Vec<T>
+    where
+        F: FnMut(&mut Self) -> Result<T, ParseError>,
+    {
+        let mut items = Vec::new();
+        loop {
+            let start = self.clone();
+            match self.optional(&mut f) {
+                Some(_) if self.rest.len() == start.rest.len() => {
+                    *self = start;
+                    break;
+                }
+                Some(item) => items.push(item),
+                None => break,
+            }
+        }
+        items
+    }
+
+    /// Parses one or more items with `f`, each separated by the text `sep`
+    pub fn separated<T, F>(&mut self, sep: &str, mut f: F) -> Result<Vec<T>, ParseError>
+    where
+        F: FnMut(&mut Self) -> Result<T, ParseError>,
+    {
+        let mut items = vec![f(self)?];
+        while self.try_literal(sep) {
+            items.push(f(self)?);
+        }
+        Ok(items)
+    }
+
+    /// Checks that all of the input has been parsed
+    pub fn end(&self) -> Result<(), ParseError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error("the end of the line"))
+        }
+    }
+}
+
+/// Parses all of `s` with `f`, failing if any text is left over
+pub fn parse_with<'a, T, F>(s: &'a str, f: F) -> Result<T, ParseError>
+where
+    F: FnOnce(&mut Parser<'a>) -> Result<T, ParseError>,
+{
+    let mut parser = Parser::new(s);
+    let value = f(&mut parser)?;
+    parser.end()?;
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbers() {
+        let tests = &[("12", Ok(12)), ("-7", Ok(-7)), ("+3", Ok(3))];
+        for (input, expected) in tests {
+            assert_eq!(&parse_with(input, Parser::signed::<i32>), expected);
+        }
+        assert_eq!(parse_with("12", Parser::unsigned::<u8>), Ok(12));
+
+        let tests = &[
+            ("-7", ("a number", "-", 1)),
+            ("x", ("a number", "x", 1)),
+            ("", ("a number", "", 1)),
+            ("1a", ("the end of the line", "a", 2)),
+        ];
+        for (input, (expected, found, column)) in tests {
+            let expected = ParseError::new(expected, found).at_column(*column);
+            assert_eq!(parse_with(input, Parser::unsigned::<u32>), Err(expected));
+        }
+        assert_eq!(
+            parse_with("300", Parser::unsigned::<u8>),
+            Err(ParseError::new("a number", "300").at_column(1))
+        );
+        assert_eq!(
+            parse_with("--1", Parser::signed::<i32>),
+            Err(ParseError::new("a number", "-").at_column(1))
+        );
+    }
+
+    #[test]
+    fn test_claim() {
+        let claim = |p: &mut Parser| {
+            p.literal("#")?;
+            let id = p.unsigned::<u32>()?;
+            p.literal(" @ ")?;
+            let origin = p.separated(",", Parser::unsigned::<u32>)?;
+            p.literal(": ")?;
+            let size = p.separated("x", Parser::unsigned::<u32>)?;
+            Ok((id, origin, size))
+        };
+        assert_eq!(
+            parse_with("#1 @ 1,3: 4x4", claim),
+            Ok((1, vec![1, 3], vec![4, 4]))
+        );
+        assert_eq!(
+            parse_with("#1 @ 1,3: 4xQ", claim),
+            Err(ParseError::new("a number", "Q").at_column(13))
+        );
+        assert_eq!(
+            parse_with("#1 @ 1,3 4x4", claim),
+            Err(ParseError::new("\": \"", " 4x4").at_column(9))
+        );
+    }
+
+    #[test]
+    fn test_whitespace() {
+        let point = |p: &mut Parser| {
+            p.literal("<")?;
+            let coords = p.separated(",", |p| {
+                p.whitespace();
+                p.signed::<i32>()
+            })?;
+            p.literal(">")?;
+            Ok(coords)
+        };
+        assert_eq!(parse_with("< 9,  -1>", point), Ok(vec![9, -1]));
+        assert_eq!(parse_with("<3>", point), Ok(vec![3]));
+        assert_eq!(
+            parse_with("< 9,  1 >", point),
+            Err(ParseError::new("\">\"", " >").at_column(8))
+        );
+    }
+
+    #[test]
+    fn test_combinators() {
+        let mut p = Parser::new("Guard #10 begins shift");
+        assert_eq!(p.word(), Ok("Guard"));
+        assert_eq!(p.optional(|p| p.literal("begins")), None);
+        assert_eq!(p.column(), 6);
+        assert!(p.try_literal(" #"));
+        assert_eq!(p.unsigned::<u32>(), Ok(10));
+        p.whitespace();
+        assert_eq!(p.rest(), "begins shift");
+        assert_eq!(
+            p.end(),
+            Err(ParseError::new("the end of the line", "begins").at_column(11))
+        );
+
+        let mut p = Parser::new("..#x");
+        let pots = p.many(|p| p.satisfy("a pot", |c| Some(c).filter(|c| "#.".contains(*c))));
+        assert_eq!(pots, vec!['.', '.', '#']);
+        assert_eq!(p.letter(), Ok('x'));
+        assert!(p.is_empty());
+        assert_eq!(
+            p.letter(),
+            Err(ParseError::new("a letter", "").at_column(5))
+        );
+
+        // items that parse no text stop the loop
+        let mut p = Parser::new("ab 1");
+        assert_eq!(
+            p.many(|p| Ok(p.take_while(char::is_whitespace))),
+            vec![""; 0]
+        );
+        assert_eq!(
+            p.many(|p| Ok(p.optional(Parser::letter))),
+            vec![Some('a'), Some('b')]
+        );
+        assert_eq!(p.rest(), " 1");
+    }
+}
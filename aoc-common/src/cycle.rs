@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A cycle in a sequence of states. After the first `prefix` states, the
+/// states repeat every `period` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub prefix: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// Returns the earliest step with the same state as step `n`
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.prefix {
+            n
+        } else {
+            self.prefix + (n - self.prefix) % self.period
+        }
+    }
+}
+
+/// Finds the cycle in the states produced by repeatedly applying `step` to
+/// `start`, using Brent's algorithm. Only a couple of states are kept at a
+/// time. Loops forever if the states never repeat.
+pub fn brent<T, F>(start: T, mut step: F) -> Cycle
+where
+    T: Clone + PartialEq,
+    F: FnMut(&T) -> T,
+{
+    // find the period by moving the tortoise to the hare at each power of two
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // find the prefix with the hare a period ahead of the tortoise
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    let mut prefix = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+    Cycle { prefix, period }
+}
+
+/// Finds the cycle in the states produced by repeatedly applying `step` to
+/// `start`, using Floyd's tortoise and hare algorithm. Loops forever if the
+/// states never repeat.
+pub fn floyd<T, F>(start: T, mut step: F) -> Cycle
+where
+    T: Clone + PartialEq,
+    F: FnMut(&T) -> T,
+{
+    // the hare moves twice as fast, so they meet somewhere in the cycle
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    // the distance from the start to the cycle is the same as the distance
+    // from the meeting point to the start of the cycle
+    let mut prefix = 0;
+    let mut tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    let mut period = 1;
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        period += 1;
+    }
+    Cycle { prefix, period }
+}
+
+/// Finds the first repeated state in a sequence by remembering every state
+/// seen. Returns None if the sequence ends without repeating.
+pub fn find_cycle<I>(states: I) -> Option<Cycle>
+where
+    I: IntoIterator,
+    I::Item: Eq + Hash,
+{
+    let mut seen = HashMap::new();
+    for (i, state) in states.into_iter().enumerate() {
+        if let Some(prefix) = seen.insert(state, i) {
+            let period = i - prefix;
+            return Some(Cycle { prefix, period });
+        }
+    }
+    None
+}
+
+/// Returns the state after applying `step` to `start` `n` times. Only
+/// enough steps to find the cycle are simulated, so `n` can be huge.
+pub fn nth_state<T, F>(start: T, mut step: F, n: usize) -> T
+where
+    T: Clone + PartialEq,
+    F: FnMut(&T) -> T,
+{
+    let cycle = brent(start.clone(), &mut step);
+    (0..cycle.reduce(n)).fold(start, |state, _| step(&state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Steps through the sequence 0, 1, 2, 3, 4, 5, 6, 7, 3, 4, ...
+    fn step(n: &u32) -> u32 {
+        if *n == 7 {
+            3
+        } else {
+            n + 1
+        }
+    }
+
+    #[test]
+    fn test_cycle() {
+        let expected = Cycle {
+            prefix: 3,
+            period: 5,
+        };
+        assert_eq!(brent(0, step), expected);
+        assert_eq!(floyd(0, step), expected);
+        let states = std::iter::successors(Some(0), |n| Some(step(n)));
+        assert_eq!(find_cycle(states), Some(expected));
+        assert_eq!(find_cycle(0..10), None);
+
+        let expected = Cycle {
+            prefix: 0,
+            period: 5,
+        };
+        assert_eq!(brent(3, step), expected);
+        assert_eq!(floyd(3, step), expected);
+
+        let expected = Cycle {
+            prefix: 0,
+            period: 1,
+        };
+        assert_eq!(brent(1, |n| *n), expected);
+        assert_eq!(floyd(1, |n| *n), expected);
+    }
+
+    #[test]
+    fn test_nth_state() {
+        let cycle = brent(0, step);
+        let tests = &[(0, 0), (2, 2), (3, 3), (7, 7), (8, 3), (1_000_000_003, 3)];
+        for (n, expected) in tests {
+            assert_eq!(&nth_state(0, step, *n), expected);
+            assert_eq!(cycle.reduce(*n), *expected as usize);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use std::fmt;
+
+/// A directed graph in the Graphviz DOT language. Nodes and edges are
+/// written in the order they are added, each with its own attributes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dot {
+    name: String,
+    nodes: Vec<String>,
+    edges: Vec<String>,
+}
+
+/// Quotes an ID, escaping any quotes and backslashes in it
+fn quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Formats attributes like `[color="#ff0000", label="A"]`, or nothing if
+/// there are none
+fn attributes(attributes: &[(&str, &str)]) -> String {
+    if attributes.is_empty() {
+        return String::new();
+    }
+    let attributes: Vec<_> = attributes
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, quote(value)))
+        .collect();
+    format!(" [{}]", attributes.join(", "))
+}
+
+impl Dot {
+    pub fn new(name: &str) -> Self {
+        Dot {
+            name: name.to_string(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    /// Adds a node with attributes such as `("label", "A")`
+    pub fn node(&mut self, id: &str, attrs: &[(&str, &str)]) {
+        self.nodes
+            .push(format!("{}{};", quote(id), attributes(attrs)));
+    }
+
+    /// Adds an edge from one node to another
+    pub fn edge(&mut self, from: &str, to: &str, attrs: &[(&str, &str)]) {
+        self.edges.push(format!(
+            "{} -> {}{};",
+            quote(from),
+            quote(to),
+            attributes(attrs)
+        ));
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len() + self.edges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.edges.is_empty()
+    }
+}
+
+impl fmt::Display for Dot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "digraph {} {{", quote(&self.name))?;
+        for line in self.nodes.iter().chain(&self.edges) {
+            writeln!(f, "  {}", line)?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dot() {
+        let mut dot = Dot::new("orbits");
+        assert!(dot.is_empty());
+        dot.node("COM", &[]);
+        dot.node("B", &[("label", "say \"hi\"")]);
+        dot.edge("COM", "B", &[("color", "#ff0000"), ("penwidth", "2")]);
+        assert_eq!(dot.len(), 3);
+
+        let expected = r##"digraph "orbits" {
+  "COM";
+  "B" [label="say \"hi\""];
+  "COM" -> "B" [color="#ff0000", penwidth="2"];
+}
+"##;
+        assert_eq!(dot.to_string(), expected);
+    }
+}
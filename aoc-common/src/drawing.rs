@@ -0,0 +1,102 @@
+use crate::{write_netpbm, Color, Dot, Format, Grid, Svg};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// A picture of a day's result, in whichever form suits it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Drawing {
+    /// An image drawn cell by cell, written as a Netpbm image
+    Image(Grid<Color>),
+    /// Lines and shapes, written as an SVG file
+    Svg(Svg),
+    /// A graph, written as a Graphviz DOT file
+    Dot(Dot),
+}
+
+/// Returns the extension of a file name, if it has one
+fn extension(path: &Path) -> Option<&str> {
+    path.extension()?.to_str()
+}
+
+impl Drawing {
+    /// Returns whether a file name ends in an extension some drawing can be
+    /// written as: `.pbm`, `.pgm`, `.ppm`, `.svg` or `.dot`
+    pub fn supports<P: AsRef<Path>>(path: P) -> bool {
+        let path = path.as_ref();
+        Format::from_path(path).is_some() || matches!(extension(path), Some("svg" | "dot"))
+    }
+
+    /// Returns the kind of file the drawing is written as
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Drawing::Image(_) => "an image",
+            Drawing::Svg(_) => "an SVG drawing",
+            Drawing::Dot(_) => "a DOT graph",
+        }
+    }
+
+    /// Writes the drawing in the format for the file name `path`, drawing
+    /// each image cell as `scale` by `scale` pixels. Fails if the drawing
+    /// cannot be written in that format.
+    pub fn write<W: Write>(&self, mut out: W, path: &Path, scale: usize) -> io::Result<()> {
+        match (self, Format::from_path(path), extension(path)) {
+            (Drawing::Image(grid), Some(format), _) => {
+                write_netpbm(out, grid, format, scale, |color| *color)
+            }
+            (Drawing::Svg(svg), _, Some("svg")) => write!(out, "{}", svg),
+            (Drawing::Dot(dot), _, Some("dot")) => write!(out, "{}", dot),
+            _ => {
+                let expected = match self {
+                    Drawing::Image(_) => ".pbm, .pgm or .ppm",
+                    Drawing::Svg(_) => ".svg",
+                    Drawing::Dot(_) => ".dot",
+                };
+                let message = format!("{} is written as {}", self.kind(), expected);
+                Err(io::Error::new(io::ErrorKind::InvalidInput, message))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Point;
+
+    #[test]
+    fn test_supports() {
+        assert!(Drawing::supports("day8.pbm"));
+        assert!(Drawing::supports("out/day3.svg"));
+        assert!(Drawing::supports("day6.dot"));
+        assert!(!Drawing::supports("day8.png"));
+        assert!(!Drawing::supports("day8"));
+    }
+
+    #[test]
+    fn test_write() {
+        let grid = Grid::parse("#.", |c| {
+            Some(if c == '#' { Color::BLACK } else { Color::WHITE })
+        })
+        .unwrap();
+        let mut out = Vec::new();
+        let image = Drawing::Image(grid);
+        image.write(&mut out, Path::new("a.pgm"), 1).unwrap();
+        assert_eq!(out, b"P5\n2 1\n255\n\x00\xff");
+
+        let mut svg = Svg::new(0);
+        svg.circle(Point(0, 0), 1, Color::BLACK);
+        let mut out = Vec::new();
+        Drawing::Svg(svg.clone())
+            .write(&mut out, Path::new("a.svg"), 1)
+            .unwrap();
+        assert_eq!(out, svg.to_string().into_bytes());
+
+        let err = image.write(Vec::new(), Path::new("a.svg"), 1).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), "an image is written as .pbm, .pgm or .ppm");
+        let err = Drawing::Dot(Dot::new("g"))
+            .write(Vec::new(), Path::new("a.ppm"), 1)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "a DOT graph is written as .dot");
+    }
+}
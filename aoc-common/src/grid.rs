@@ -0,0 +1,468 @@
+use crate::{render, ParseError, Point};
+use std::fmt;
+use std::ops;
+
+/// Offsets to the four neighbors sharing an edge, in reading order
+const NEIGHBORS_4: &[(isize, isize)] = &[(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// Offsets to the eight neighbors sharing an edge or a corner, in reading
+/// order
+const NEIGHBORS_8: &[(isize, isize)] = &[
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// A grid is a rectangle of cells, stored row by row. Cells are indexed by
+/// `Point(x, y)`, with `Point(0, 0)` at the top-left.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// Creates a grid with every cell set to `value`
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        let cells = vec![value; width * height];
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid from cells in row order
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Self {
+        let height = if width == 0 {
+            assert!(cells.is_empty(), "grid with no width has cells");
+            0
+        } else {
+            assert!(
+                cells.len().is_multiple_of(width),
+                "{} cells do not fill rows of width {}",
+                cells.len(),
+                width
+            );
+            cells.len() / width
+        };
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parses a map with one character per cell, and one line per row. Each
+    /// character is converted to a cell by `cell`, which returns None for an
+    /// invalid character.
+    pub fn parse<F: FnMut(char) -> Option<T>>(s: &str, mut cell: F) -> Result<Self, ParseError> {
+        let mut width = None;
+        let mut cells = Vec::new();
+        for (y, line) in s.lines().enumerate() {
+            let len = line.chars().count();
+            match width {
+                None => width = Some(len),
+                Some(width) if width != len => {
+                    let expected = format!("a row of length {}", width);
+                    return Err(ParseError::new(&expected, line).at_line(y + 1));
+                }
+                Some(_) => {}
+            }
+            for (x, c) in line.chars().enumerate() {
+                let value = cell(c).ok_or_else(|| {
+                    ParseError::new("a map cell", &c.to_string())
+                        .at_line(y + 1)
+                        .at_column(x + 1)
+                })?;
+                cells.push(value);
+            }
+        }
+        Ok(Grid::from_vec(width.unwrap_or(0), cells))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns true if the point is inside the grid
+    pub fn contains(&self, point: Point<usize>) -> bool {
+        point.0 < self.width && point.1 < self.height
+    }
+
+    /// Returns true if the point is on the outer edge of the grid
+    pub fn is_border(&self, point: Point<usize>) -> bool {
+        self.contains(point)
+            && (point.0 == 0
+                || point.1 == 0
+                || point.0 == self.width - 1
+                || point.1 == self.height - 1)
+    }
+
+    fn index(&self, point: Point<usize>) -> Option<usize> {
+        if self.contains(point) {
+            Some(point.1 * self.width + point.0)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, point: Point<usize>) -> Option<&T> {
+        self.index(point).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, point: Point<usize>) -> Option<&mut T> {
+        self.index(point).map(move |i| &mut self.cells[i])
+    }
+
+    /// Returns every point in the grid in row order
+    pub fn points(&self) -> impl Iterator<Item = Point<usize>> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Point(x, y)))
+    }
+
+    /// Returns every point in the grid along with its cell, in row order
+    pub fn iter(&self) -> impl Iterator<Item = (Point<usize>, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        if y < self.height {
+            Some(&self.cells[y * self.width..(y + 1) * self.width])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the cells in column `x`, from top to bottom. The column is
+    /// empty if `x` is outside the grid.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        let height = if x < self.width { self.height } else { 0 };
+        (0..height).map(move |y| &self.cells[y * self.width + x])
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    fn neighbors<'a>(
+        &'a self,
+        point: Point<usize>,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = Point<usize>> + 'a {
+        offsets.iter().filter_map(move |(dx, dy)| {
+            let x = point.0.checked_add_signed(*dx)?;
+            let y = point.1.checked_add_signed(*dy)?;
+            Some(Point(x, y)).filter(|point| self.contains(*point))
+        })
+    }
+
+    /// Returns the points inside the grid which share an edge with `point`
+    pub fn neighbors4(&self, point: Point<usize>) -> impl Iterator<Item = Point<usize>> + '_ {
+        self.neighbors(point, NEIGHBORS_4)
+    }
+
+    /// Returns the points inside the grid which share an edge or a corner
+    /// with `point`
+    pub fn neighbors8(&self, point: Point<usize>) -> impl Iterator<Item = Point<usize>> + '_ {
+        self.neighbors(point, NEIGHBORS_8)
+    }
+
+    /// Returns a view of part of the grid, or None if the view does not fit
+    /// inside the grid
+    pub fn view(&self, origin: Point<usize>, width: usize, height: usize) -> Option<View<'_, T>> {
+        if origin.0 + width > self.width || origin.1 + height > self.height {
+            return None;
+        }
+        Some(View {
+            grid: self,
+            origin,
+            width,
+            height,
+        })
+    }
+
+    /// Creates a grid of the same size by converting each cell
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        let cells = self.cells.iter().map(f).collect();
+        Grid::from_vec(self.width, cells)
+    }
+
+    /// Renders the grid, one row per line, with the character for each cell
+    /// returned by `cell`
+    pub fn render<F: Fn(&T) -> char>(&self, cell: F) -> String {
+        render(self.width, self.height, |x, y| cell(&self[Point(x, y)]))
+    }
+}
+
+impl<T> ops::Index<Point<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point<usize>) -> &T {
+        self.get(point)
+            .unwrap_or_else(|| panic!("point {} is outside the grid", point))
+    }
+}
+
+impl<T> ops::IndexMut<Point<usize>> for Grid<T> {
+    fn index_mut(&mut self, point: Point<usize>) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("point {} is outside the {}x{} grid", point, width, height))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y != 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A view of a rectangle inside a grid. Points are relative to the top-left
+/// of the view.
+#[derive(Debug, Clone, Copy)]
+pub struct View<'a, T> {
+    grid: &'a Grid<T>,
+    origin: Point<usize>,
+    width: usize,
+    height: usize,
+}
+
+impl<'a, T> View<'a, T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the position of the view's top-left corner in the grid
+    pub fn origin(&self) -> Point<usize> {
+        self.origin
+    }
+
+    pub fn get(&self, point: Point<usize>) -> Option<&'a T> {
+        if point.0 < self.width && point.1 < self.height {
+            self.grid.get(self.origin + point)
+        } else {
+            None
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> {
+        let (grid, Point(x, y), width) = (self.grid, self.origin, self.width);
+        (y..y + self.height).map(move |y| &grid.row(y).unwrap()[x..x + width])
+    }
+
+    /// Returns every point in the view along with its cell, in row order
+    pub fn iter(&self) -> impl Iterator<Item = (Point<usize>, &'a T)> {
+        let width = self.width;
+        self.rows().enumerate().flat_map(move |(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(x, cell)| (Point(x, y), cell))
+                .take(width)
+        })
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for View<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y != 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = "#..#\n.##.\n#...";
+
+    fn parse_map() -> Grid<bool> {
+        Grid::parse(MAP, |c| Some(c == '#')).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = parse_map();
+        assert_eq!((grid.width(), grid.height()), (4, 3));
+        assert_eq!(grid.get(Point(0, 0)), Some(&true));
+        assert_eq!(grid.get(Point(1, 0)), Some(&false));
+        assert_eq!(grid.get(Point(2, 1)), Some(&true));
+        assert_eq!(grid.get(Point(4, 0)), None);
+        assert_eq!(grid.get(Point(0, 3)), None);
+        assert_eq!(grid.render(|c| if *c { '#' } else { '.' }), MAP);
+
+        let grid = Grid::parse(MAP, Some).unwrap();
+        assert_eq!(grid.to_string(), MAP);
+
+        let grid = Grid::parse("", Some).unwrap();
+        assert_eq!((grid.width(), grid.height()), (0, 0));
+        assert_eq!(grid.to_string(), "");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let cell = |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        };
+        let tests = &[
+            (
+                "#..#\n.#.",
+                ParseError::new("a row of length 4", ".#.").at_line(2),
+            ),
+            (
+                "#..#\n.#x.",
+                ParseError::new("a map cell", "x").at_line(2).at_column(3),
+            ),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(Grid::parse(input, cell).as_ref(), Err(expected));
+        }
+    }
+
+    #[test]
+    fn test_index() {
+        let mut grid = Grid::new(3, 2, 0);
+        grid[Point(2, 1)] = 5;
+        *grid.get_mut(Point(0, 1)).unwrap() = 3;
+        assert!(grid.get_mut(Point(3, 1)).is_none());
+        assert_eq!(grid[Point(2, 1)], 5);
+        assert_eq!(grid.to_string(), "000\n305");
+        assert_eq!(grid, Grid::from_vec(3, vec![0, 0, 0, 3, 0, 5]));
+    }
+
+    #[test]
+    #[should_panic(expected = "point 3,0 is outside the grid")]
+    fn test_index_out_of_bounds() {
+        let grid = Grid::new(3, 2, 0);
+        let _ = grid[Point(3, 0)];
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = parse_map();
+        let tests = &[
+            (Point(0, 0), vec![Point(1, 0), Point(0, 1)]),
+            (
+                Point(1, 1),
+                vec![Point(1, 0), Point(0, 1), Point(2, 1), Point(1, 2)],
+            ),
+            (Point(3, 2), vec![Point(3, 1), Point(2, 2)]),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(&grid.neighbors4(*input).collect::<Vec<_>>(), expected);
+        }
+
+        let tests = &[
+            (Point(0, 0), vec![Point(1, 0), Point(0, 1), Point(1, 1)]),
+            (
+                Point(1, 1),
+                vec![
+                    Point(0, 0),
+                    Point(1, 0),
+                    Point(2, 0),
+                    Point(0, 1),
+                    Point(2, 1),
+                    Point(0, 2),
+                    Point(1, 2),
+                    Point(2, 2),
+                ],
+            ),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(&grid.neighbors8(*input).collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn test_rows_columns() {
+        let grid = Grid::parse(MAP, Some).unwrap();
+        let rows: Vec<String> = grid.rows().map(|row| row.iter().collect()).collect();
+        assert_eq!(rows, vec!["#..#", ".##.", "#..."]);
+        assert_eq!(grid.row(1), Some(&['.', '#', '#', '.'][..]));
+        assert_eq!(grid.row(3), None);
+
+        let columns: Vec<String> = grid.columns().map(|column| column.collect()).collect();
+        assert_eq!(columns, vec!["#.#", ".#.", ".#.", "#.."]);
+        assert_eq!(grid.column(4).count(), 0);
+    }
+
+    #[test]
+    fn test_points() {
+        let grid = parse_map();
+        let on: Vec<_> = grid
+            .iter()
+            .filter(|(_, on)| **on)
+            .map(|(point, _)| point)
+            .collect();
+        assert_eq!(
+            on,
+            vec![
+                Point(0, 0),
+                Point(3, 0),
+                Point(1, 1),
+                Point(2, 1),
+                Point(0, 2)
+            ]
+        );
+        assert_eq!(grid.points().count(), 12);
+
+        let border = grid.points().filter(|point| grid.is_border(*point)).count();
+        assert_eq!(border, 10);
+    }
+
+    #[test]
+    fn test_view() {
+        let grid = Grid::parse(MAP, Some).unwrap();
+        let view = grid.view(Point(1, 1), 3, 2).unwrap();
+        assert_eq!(view.to_string(), "##.\n...");
+        assert_eq!(view.get(Point(0, 0)), Some(&'#'));
+        assert_eq!(view.get(Point(2, 1)), Some(&'.'));
+        assert_eq!(view.get(Point(3, 0)), None);
+        assert_eq!(view.iter().filter(|(_, c)| **c == '#').count(), 2);
+        assert_eq!(view.iter().last(), Some((Point(2, 1), &'.')));
+        assert!(grid.view(Point(2, 2), 3, 1).is_none());
+    }
+
+    #[test]
+    fn test_map() {
+        let grid = parse_map().map(|on| if *on { 1 } else { 0 });
+        assert_eq!(grid.to_string(), "1001\n0110\n1000");
+    }
+}
@@ -0,0 +1,145 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The environment variable that overrides the input cache directory
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// A directory of puzzle inputs laid out by year and day. Each named input
+/// is a file like `2018/6/example2.input`, and the expected output for an
+/// input, if any, is a file like `2018/10/example.solution`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputCache {
+    dir: PathBuf,
+}
+
+impl InputCache {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        let dir = dir.into();
+        InputCache { dir }
+    }
+
+    /// Returns the cache in the directory named by `AOC_INPUT_DIR`, or in
+    /// `aoc` under the user's cache directory. Returns None if there is no
+    /// such directory.
+    pub fn from_env() -> Option<Self> {
+        let dir = match env::var_os(INPUT_DIR_VAR) {
+            Some(dir) => PathBuf::from(dir),
+            None => env::var_os("XDG_CACHE_HOME")
+                .map(PathBuf::from)
+                .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))?
+                .join("aoc"),
+        };
+        Some(InputCache::new(dir)).filter(|cache| cache.dir.is_dir())
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn day_dir(&self, year: u32, day: u32) -> PathBuf {
+        self.dir.join(year.to_string()).join(day.to_string())
+    }
+
+    /// Returns the path of a named input for a day
+    pub fn path(&self, year: u32, day: u32, name: &str) -> PathBuf {
+        self.day_dir(year, day).join(format!("{}.input", name))
+    }
+
+    /// Returns the path of the expected output for a named input
+    pub fn solution_path(&self, year: u32, day: u32, name: &str) -> PathBuf {
+        self.day_dir(year, day).join(format!("{}.solution", name))
+    }
+
+    /// Reads a named input for a day. Returns None if it is not cached.
+    pub fn read(&self, year: u32, day: u32, name: &str) -> io::Result<Option<String>> {
+        read_if_exists(&self.path(year, day, name))
+    }
+
+    /// Reads the expected output for a named input. Returns None if it is
+    /// not cached.
+    pub fn read_solution(&self, year: u32, day: u32, name: &str) -> io::Result<Option<String>> {
+        read_if_exists(&self.solution_path(year, day, name))
+    }
+
+    /// Writes a named input for a day, creating the day's directory if
+    /// needed
+    pub fn write(&self, year: u32, day: u32, name: &str, input: &str) -> io::Result<()> {
+        fs::create_dir_all(self.day_dir(year, day))?;
+        fs::write(self.path(year, day, name), input)
+    }
+
+    /// Returns the names of the cached inputs for a day in order
+    pub fn names(&self, year: u32, day: u32) -> io::Result<Vec<String>> {
+        let entries = match fs::read_dir(self.day_dir(year, day)) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        let mut names = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "input") {
+                if let Some(name) = path.file_stem().and_then(|name| name.to_str()) {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+}
+
+fn read_if_exists(path: &Path) -> io::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(s) => Ok(Some(s)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EXAMPLE, INPUT};
+
+    /// Returns an empty directory under the system temporary directory
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_input_cache() {
+        let dir = temp_dir("input-cache");
+        let cache = InputCache::new(&dir);
+        assert_eq!(
+            cache.path(2018, 6, "example2"),
+            dir.join("2018/6/example2.input")
+        );
+        assert_eq!(cache.read(2018, 6, INPUT).unwrap(), None);
+        assert_eq!(cache.names(2018, 6).unwrap(), Vec::<String>::new());
+
+        cache.write(2018, 6, INPUT, "1, 1\n").unwrap();
+        cache.write(2018, 6, "example2", "1, 6\n").unwrap();
+        cache.write(2018, 6, EXAMPLE, "8, 3\n").unwrap();
+        fs::write(cache.solution_path(2018, 6, EXAMPLE), "17\n").unwrap();
+        assert_eq!(
+            cache.read(2018, 6, INPUT).unwrap(),
+            Some("1, 1\n".to_string())
+        );
+        assert_eq!(
+            cache.read_solution(2018, 6, EXAMPLE).unwrap(),
+            Some("17\n".to_string())
+        );
+        assert_eq!(cache.read_solution(2018, 6, INPUT).unwrap(), None);
+        assert_eq!(
+            cache.names(2018, 6).unwrap(),
+            vec![EXAMPLE, "example2", INPUT]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
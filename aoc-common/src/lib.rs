@@ -0,0 +1,37 @@
+mod answers;
+mod bench;
+mod color;
+mod combinator;
+mod cycle;
+mod dot;
+mod drawing;
+mod grid;
+mod inputs;
+mod netpbm;
+mod ocr;
+mod parse;
+mod path;
+mod point;
+mod render;
+mod solution;
+mod sparse_grid;
+mod svg;
+
+pub use answers::{check, encode_answer, Answers, Check, Status};
+pub use bench::{bench, Benchmark, Report, Timing};
+pub use color::Color;
+pub use combinator::{parse_with, Parser};
+pub use cycle::{brent, find_cycle, floyd, nth_state, Cycle};
+pub use dot::Dot;
+pub use drawing::Drawing;
+pub use grid::{Grid, View};
+pub use inputs::{InputCache, INPUT_DIR_VAR};
+pub use netpbm::{write_netpbm, Format};
+pub use ocr::{ocr, ocr_str};
+pub use parse::{parse_comma_separated, parse_digits, parse_lines, parse_number, ParseError};
+pub use path::{astar, bfs, bfs_multi, dijkstra, Paths};
+pub use point::{manhattan_distance, Point};
+pub use render::render;
+pub use solution::{Answer, Registry, Solution, SolveError, EXAMPLE, INPUT};
+pub use sparse_grid::SparseGrid;
+pub use svg::Svg;
@@ -0,0 +1,128 @@
+use crate::{Color, Grid};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// A Netpbm image format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Black and white, with each pixel black if its color is dark
+    Pbm,
+    /// Grayscale
+    Pgm,
+    /// Full color
+    Ppm,
+}
+
+impl Format {
+    /// Returns the format for a file name ending in `.pbm`, `.pgm` or
+    /// `.ppm`
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        match path.as_ref().extension()?.to_str()? {
+            "pbm" => Some(Format::Pbm),
+            "pgm" => Some(Format::Pgm),
+            "ppm" => Some(Format::Ppm),
+            _ => None,
+        }
+    }
+}
+
+/// Writes a grid as a binary Netpbm image. Each cell is drawn as a `scale`
+/// by `scale` square in the color returned by `palette`.
+pub fn write_netpbm<T, W, F>(
+    mut out: W,
+    grid: &Grid<T>,
+    format: Format,
+    scale: usize,
+    palette: F,
+) -> io::Result<()>
+where
+    W: Write,
+    F: Fn(&T) -> Color,
+{
+    let width = grid.width() * scale;
+    let height = grid.height() * scale;
+    match format {
+        Format::Pbm => write!(out, "P4\n{} {}\n", width, height)?,
+        Format::Pgm => write!(out, "P5\n{} {}\n255\n", width, height)?,
+        Format::Ppm => write!(out, "P6\n{} {}\n255\n", width, height)?,
+    }
+
+    for row in grid.rows() {
+        let colors: Vec<_> = row
+            .iter()
+            .flat_map(|cell| std::iter::repeat_n(palette(cell), scale))
+            .collect();
+        let line = match format {
+            // each row is packed eight pixels to a byte, with 1 for black
+            Format::Pbm => colors
+                .chunks(8)
+                .map(|pixels| {
+                    pixels
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, color)| color.luma() < 128)
+                        .fold(0u8, |byte, (i, _)| byte | 0x80 >> i)
+                })
+                .collect(),
+            Format::Pgm => colors.iter().map(Color::luma).collect(),
+            Format::Ppm => colors
+                .iter()
+                .flat_map(|Color(r, g, b)| vec![*r, *g, *b])
+                .collect::<Vec<u8>>(),
+        };
+        for _ in 0..scale {
+            out.write_all(&line)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        assert_eq!(Format::from_path("day8.pbm"), Some(Format::Pbm));
+        assert_eq!(Format::from_path("out/day6.ppm"), Some(Format::Ppm));
+        assert_eq!(Format::from_path("day8.png"), None);
+        assert_eq!(Format::from_path("day8"), None);
+    }
+
+    #[test]
+    fn test_write_netpbm() {
+        let grid = Grid::parse("#.\n.#", |c| Some(c == '#')).unwrap();
+        let palette = |on: &bool| {
+            if *on {
+                Color::BLACK
+            } else {
+                Color(255, 255, 0)
+            }
+        };
+
+        let mut out = Vec::new();
+        write_netpbm(&mut out, &grid, Format::Pbm, 1, palette).unwrap();
+        assert_eq!(out, b"P4\n2 2\n\x80\x40");
+
+        let mut out = Vec::new();
+        write_netpbm(&mut out, &grid, Format::Pgm, 1, palette).unwrap();
+        assert_eq!(out, b"P5\n2 2\n255\n\x00\xe1\xe1\x00");
+
+        let mut out = Vec::new();
+        write_netpbm(&mut out, &grid, Format::Ppm, 1, palette).unwrap();
+        assert_eq!(
+            out,
+            b"P6\n2 2\n255\n\x00\x00\x00\xff\xff\x00\xff\xff\x00\x00\x00\x00"
+        );
+
+        let mut out = Vec::new();
+        write_netpbm(&mut out, &grid, Format::Pbm, 5, palette).unwrap();
+        let mut expected = b"P4\n10 10\n".to_vec();
+        for row in &[[0xf8, 0x00], [0x07, 0xc0]] {
+            for _ in 0..5 {
+                expected.extend(row);
+            }
+        }
+        assert_eq!(out, expected);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::{Grid, ParseError};
+
+/// The letters of the 6 row block font, with each glyph trimmed to the
+/// columns it uses
+const FONT_6: &[(char, &str)] = &[
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', "###\n.#.\n.#.\n.#.\n.#.\n###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+/// The letters of the 10 row block font, with each glyph trimmed to the
+/// columns it uses
+const FONT_10: &[(char, &str)] = &[
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+/// Reads the letters in a grid drawn in the block font used by puzzles
+/// whose answer is an image, like the 2019 day 8 password. The font is 6 or
+/// 10 rows high, and `lit` returns whether a cell is part of a letter.
+/// Blank rows around the letters are ignored, and letters are separated by
+/// blank columns. Fails if a glyph is not a known letter.
+pub fn ocr<T, F: Fn(&T) -> bool>(grid: &Grid<T>, lit: F) -> Result<String, ParseError> {
+    let lit = grid.map(lit);
+    let blank_row = |y: usize| lit.row(y).is_none_or(|row| row.iter().all(|cell| !cell));
+    let blank_column = |x: usize| lit.column(x).all(|cell| !cell);
+
+    let rows: Vec<_> = (0..lit.height()).filter(|y| !blank_row(*y)).collect();
+    let (top, bottom) = match (rows.first(), rows.last()) {
+        (Some(top), Some(bottom)) => (*top, *bottom),
+        _ => return Ok(String::new()),
+    };
+    let font = match bottom - top + 1 {
+        6 => FONT_6,
+        10 => FONT_10,
+        height => {
+            let found = format!("{} rows", height);
+            return Err(ParseError::new("letters 6 or 10 rows high", &found));
+        }
+    };
+
+    let mut letters = String::new();
+    let mut x = 0;
+    while x < lit.width() {
+        if blank_column(x) {
+            x += 1;
+            continue;
+        }
+        let start = x;
+        while x < lit.width() && !blank_column(x) {
+            x += 1;
+        }
+        let glyph = (top..=bottom)
+            .map(|y| {
+                (start..x)
+                    .map(|x| if lit.row(y).unwrap()[x] { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let letter = font
+            .iter()
+            .find(|(_, g)| *g == glyph)
+            .map(|(letter, _)| *letter)
+            .ok_or_else(|| ParseError::new("a letter", &glyph).at_column(start + 1))?;
+        letters.push(letter);
+    }
+    Ok(letters)
+}
+
+/// Reads the letters in a rendered image, with `#` for the cells that are
+/// part of a letter and `.` or a space for the others
+pub fn ocr_str(s: &str) -> Result<String, ParseError> {
+    let grid = Grid::parse(s, |c| match c {
+        '#' => Some(true),
+        '.' | ' ' => Some(false),
+        _ => None,
+    })?;
+    ocr(&grid, |cell| *cell)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fonts() {
+        for (font, height) in &[(FONT_6, 6), (FONT_10, 10)] {
+            for (letter, glyph) in font.iter() {
+                assert_eq!(ocr_str(glyph), Ok(letter.to_string()));
+                assert_eq!(glyph.lines().count(), *height);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ocr_str() {
+        let image = "\
+..........
+.#..#.###.
+.#..#..#..
+.####..#..
+.#..#..#..
+.#..#..#..
+.#..#.###.
+..........";
+        assert_eq!(ocr_str(image), Ok("HI".to_string()));
+        assert_eq!(ocr_str("....\n...."), Ok(String::new()));
+
+        let image = "\
+#..#.#..#
+#..#.##.#
+####.#.##
+#..#.#..#
+#..#.#..#
+#..#.#..#";
+        let err = ocr_str(image).unwrap_err();
+        assert_eq!(err.expected(), "a letter");
+        assert_eq!(err.column(), Some(6));
+        assert_eq!(
+            ocr_str("#\n#\n#").unwrap_err(),
+            ParseError::new("letters 6 or 10 rows high", "3 rows")
+        );
+        assert!(ocr_str("#x").is_err());
+    }
+
+    #[test]
+    fn test_ocr() {
+        let grid = Grid::parse("0110\n1001\n1001\n1111\n1001\n1001", |c| c.to_digit(10)).unwrap();
+        assert_eq!(ocr(&grid, |n| *n == 1), Ok("A".to_string()));
+    }
+}
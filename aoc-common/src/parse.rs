@@ -0,0 +1,191 @@
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+/// An error in the puzzle input. It records the text which could not be
+/// parsed and what was expected instead, along with where it was found when
+/// that is known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    line: Option<usize>,
+    column: Option<usize>,
+    text: String,
+    expected: String,
+}
+
+impl ParseError {
+    /// Creates an error for `text`, which is not `expected`. The expected
+    /// value is a description, like "a number".
+    pub fn new(expected: &str, text: &str) -> Self {
+        let text = text.to_string();
+        let expected = expected.to_string();
+        ParseError {
+            line: None,
+            column: None,
+            text,
+            expected,
+        }
+    }
+
+    /// Sets the line number, starting at 1, unless it is already set
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line.get_or_insert(line);
+        self
+    }
+
+    /// Sets the column number, starting at 1, unless it is already set
+    pub fn at_column(mut self, column: usize) -> Self {
+        self.column.get_or_insert(column);
+        self
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    pub fn column(&self) -> Option<usize> {
+        self.column
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn expected(&self) -> &str {
+        &self.expected
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "line {}, column {}: ", line, column)?,
+            (Some(line), None) => write!(f, "line {}: ", line)?,
+            (None, Some(column)) => write!(f, "column {}: ", column)?,
+            (None, None) => {}
+        }
+        write!(f, "expected {}, found {:?}", self.expected, self.text)
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// Parses a number, ignoring whitespace around it
+pub fn parse_number<T: FromStr>(s: &str) -> Result<T, ParseError> {
+    s.trim()
+        .parse()
+        .map_err(|_| ParseError::new("a number", s.trim()))
+}
+
+/// Parses each line with `parse`, and adds the line number to any error
+pub fn parse_lines<T, F>(s: &str, mut parse: F) -> Result<Vec<T>, ParseError>
+where
+    F: FnMut(&str) -> Result<T, ParseError>,
+{
+    s.lines()
+        .enumerate()
+        .map(|(i, line)| parse(line).map_err(|err| err.at_line(i + 1)))
+        .collect()
+}
+
+/// Parses a comma-separated list of numbers, like an Intcode program.
+/// Whitespace around each number is ignored. Errors include the column of
+/// the invalid number.
+pub fn parse_comma_separated<T: FromStr>(s: &str) -> Result<Vec<T>, ParseError> {
+    if s.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut column = 1;
+    s.split(',')
+        .map(|item| {
+            let start = column + item.chars().take_while(|c| c.is_whitespace()).count();
+            column += item.chars().count() + 1;
+            parse_number(item).map_err(|err| err.at_column(start))
+        })
+        .collect()
+}
+
+/// Parses a string of decimal digits, like "0123", ignoring whitespace
+/// around it. Errors include the column of the invalid character.
+pub fn parse_digits(s: &str) -> Result<Vec<u32>, ParseError> {
+    let skipped = s.chars().take_while(|c| c.is_whitespace()).count();
+    s.trim()
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            c.to_digit(10).ok_or_else(|| {
+                ParseError::new("a digit", &c.to_string()).at_column(skipped + i + 1)
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error() {
+        let tests = &[
+            (
+                ParseError::new("a number", "x"),
+                "expected a number, found \"x\"",
+            ),
+            (
+                ParseError::new("a direction", "Q5").at_line(3),
+                "line 3: expected a direction, found \"Q5\"",
+            ),
+            (
+                ParseError::new("a number", "").at_column(4).at_line(2),
+                "line 2, column 4: expected a number, found \"\"",
+            ),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(&input.to_string(), expected);
+        }
+
+        let err = ParseError::new("a number", "x").at_line(2).at_line(5);
+        assert_eq!(err.line(), Some(2));
+        assert_eq!((err.text(), err.expected()), ("x", "a number"));
+    }
+
+    #[test]
+    fn test_parse_number() {
+        assert_eq!(parse_number::<i32>(" -12\n"), Ok(-12));
+        assert_eq!(parse_number::<i32>("+3"), Ok(3));
+        assert_eq!(
+            parse_number::<u8>("256"),
+            Err(ParseError::new("a number", "256"))
+        );
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        assert_eq!(parse_lines("1\n2\n", parse_number::<u32>), Ok(vec![1, 2]));
+        assert_eq!(
+            parse_lines("1\n2\nthree\n4", parse_number::<u32>),
+            Err(ParseError::new("a number", "three").at_line(3))
+        );
+    }
+
+    #[test]
+    fn test_parse_comma_separated() {
+        assert_eq!(parse_comma_separated::<i64>("1,-2,3\n"), Ok(vec![1, -2, 3]));
+        assert_eq!(parse_comma_separated::<usize>("1, 6"), Ok(vec![1, 6]));
+        assert_eq!(parse_comma_separated::<i64>(""), Ok(vec![]));
+        assert_eq!(
+            parse_comma_separated::<usize>("1,22, x"),
+            Err(ParseError::new("a number", "x").at_column(7))
+        );
+    }
+
+    #[test]
+    fn test_parse_digits() {
+        assert_eq!(parse_digits("0123\n"), Ok(vec![0, 1, 2, 3]));
+        assert_eq!(parse_digits(""), Ok(vec![]));
+        assert_eq!(
+            parse_digits(" 12a"),
+            Err(ParseError::new("a digit", "a").at_column(4))
+        );
+    }
+}
@@ -0,0 +1,377 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// The result of a search: the cost of the shortest path from the start to
+/// every node reached, and the previous node on each path
+#[derive(Debug, Clone)]
+pub struct Paths<N, C> {
+    costs: HashMap<N, C>,
+    parents: HashMap<N, N>,
+}
+
+impl<N: Eq + Hash + Clone, C: Copy> Paths<N, C> {
+    fn new() -> Self {
+        let costs = HashMap::new();
+        let parents = HashMap::new();
+        Paths { costs, parents }
+    }
+
+    /// Returns the cost of the shortest path to the node, or None if the
+    /// node was not reached
+    pub fn cost(&self, node: &N) -> Option<C> {
+        self.costs.get(node).copied()
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.costs.contains_key(node)
+    }
+
+    /// Returns the number of nodes reached, including the start
+    pub fn len(&self) -> usize {
+        self.costs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.costs.is_empty()
+    }
+
+    /// Returns every node reached along with its cost, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&N, C)> {
+        self.costs.iter().map(|(node, cost)| (node, *cost))
+    }
+
+    /// Returns the nodes on the shortest path from the start to `node`,
+    /// including both ends, or None if the node was not reached
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        if !self.contains(node) {
+            return None;
+        }
+        let mut path = vec![node.clone()];
+        let mut node = node;
+        while let Some(parent) = self.parents.get(node) {
+            path.push(parent.clone());
+            node = parent;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Finds the number of steps to every node reachable from `start`, where
+/// each step moves to one of the nodes returned by `successors`
+pub fn bfs<N, F, I>(start: N, successors: F) -> Paths<N, usize>
+where
+    N: Eq + Hash + Clone,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    bfs_multi(vec![start], successors)
+}
+
+/// Finds the number of steps to every node reachable from any of the
+/// starting nodes. Each node is reached from its closest start, so this
+/// floods outwards from all of them at once.
+pub fn bfs_multi<N, S, F, I>(starts: S, mut successors: F) -> Paths<N, usize>
+where
+    N: Eq + Hash + Clone,
+    S: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut paths = Paths::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if !paths.contains(&start) {
+            paths.costs.insert(start.clone(), 0);
+            queue.push_back(start);
+        }
+    }
+    while let Some(node) = queue.pop_front() {
+        let steps = paths.costs[&node] + 1;
+        for next in successors(&node) {
+            if paths.contains(&next) {
+                continue;
+            }
+            paths.costs.insert(next.clone(), steps);
+            paths.parents.insert(next.clone(), node.clone());
+            queue.push_back(next);
+        }
+    }
+    paths
+}
+
+/// A node waiting in the priority queue. The queue is a max-heap, so nodes
+/// are ordered by reversed priority.
+struct Queued<N, C> {
+    priority: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> PartialEq for Queued<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N, C: Ord> Eq for Queued<N, C> {}
+
+impl<N, C: Ord> PartialOrd for Queued<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for Queued<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+/// Searches outwards from `start` in order of cost, where `successors`
+/// returns each next node along with the cost of moving to it. Stops early
+/// when `goal` returns true for a node, and returns that node.
+fn search<N, C, F, I, H, G>(
+    start: N,
+    mut successors: F,
+    mut heuristic: H,
+    mut goal: G,
+) -> (Paths<N, C>, Option<N>)
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    H: FnMut(&N) -> C,
+    G: FnMut(&N) -> bool,
+{
+    let mut paths = Paths::new();
+    let mut queue = BinaryHeap::new();
+    paths.costs.insert(start.clone(), C::default());
+    queue.push(Queued {
+        priority: heuristic(&start),
+        cost: C::default(),
+        node: start,
+    });
+    while let Some(Queued { cost, node, .. }) = queue.pop() {
+        if paths.cost(&node).is_some_and(|best| cost > best) {
+            continue;
+        }
+        if goal(&node) {
+            return (paths, Some(node));
+        }
+        for (next, step) in successors(&node) {
+            let cost = cost + step;
+            if paths.cost(&next).is_some_and(|best| best <= cost) {
+                continue;
+            }
+            paths.costs.insert(next.clone(), cost);
+            paths.parents.insert(next.clone(), node.clone());
+            queue.push(Queued {
+                priority: cost + heuristic(&next),
+                cost,
+                node: next,
+            });
+        }
+    }
+    (paths, None)
+}
+
+/// Finds the lowest cost to every node reachable from `start`, where
+/// `successors` returns each next node along with the cost of moving to it
+pub fn dijkstra<N, C, F, I>(start: N, successors: F) -> Paths<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+{
+    search(start, successors, |_| C::default(), |_| false).0
+}
+
+/// Finds the lowest cost path from `start` to a node for which `goal`
+/// returns true. The `heuristic` estimates the remaining cost from a node to
+/// the goal, and must never overestimate it. Returns the path, including
+/// both ends, and its cost.
+pub fn astar<N, C, F, I, H, G>(
+    start: N,
+    successors: F,
+    heuristic: H,
+    goal: G,
+) -> Option<(Vec<N>, C)>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    H: FnMut(&N) -> C,
+    G: FnMut(&N) -> bool,
+{
+    let (paths, end) = search(start, successors, heuristic, goal);
+    let end = end?;
+    let cost = paths.cost(&end)?;
+    Some((paths.path_to(&end)?, cost))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{manhattan_distance, Grid, Point};
+
+    /// The example mazes from 2019 day 18, with the fewest steps to collect
+    /// every key
+    const MAZES: &[(&str, usize)] = &[
+        (
+            "\
+#########
+#b.A.@.a#
+#########",
+            8,
+        ),
+        (
+            "\
+########################
+#f.D.E.e.C.b.A.@.a.B.c.#
+######################.#
+#d.....................#
+########################",
+            86,
+        ),
+        (
+            "\
+########################
+#...............b.C.D.f#
+#.######################
+#.....@.a.B.c.d.A.e.F.g#
+########################",
+            132,
+        ),
+        (
+            "\
+########################
+#@..............ac.GI.b#
+###d#e#f################
+###A#B#C################
+###g#h#i################
+########################",
+            81,
+        ),
+    ];
+
+    fn parse_maze(s: &str) -> (Grid<char>, Point<usize>) {
+        let grid = Grid::parse(s, Some).unwrap();
+        let (start, _) = grid.iter().find(|(_, c)| **c == '@').unwrap();
+        (grid, start)
+    }
+
+    fn open_neighbors(grid: &Grid<char>, point: Point<usize>) -> Vec<Point<usize>> {
+        grid.neighbors4(point)
+            .filter(|point| grid[*point] != '#')
+            .collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let (grid, start) = parse_maze(MAZES[0].0);
+        let paths = bfs(start, |point| open_neighbors(&grid, *point));
+        assert_eq!(paths.len(), 7);
+        assert_eq!(paths.cost(&start), Some(0));
+        assert_eq!(paths.cost(&Point(7, 1)), Some(2));
+        assert_eq!(paths.cost(&Point(1, 1)), Some(4));
+        assert_eq!(paths.cost(&Point(0, 0)), None);
+        assert_eq!(
+            paths.path_to(&Point(7, 1)),
+            Some(vec![Point(5, 1), Point(6, 1), Point(7, 1)])
+        );
+
+        // doors block the way until their key is collected
+        let paths = bfs(start, |point| {
+            open_neighbors(&grid, *point)
+                .into_iter()
+                .filter(|point| !grid[*point].is_ascii_uppercase())
+        });
+        assert_eq!(paths.cost(&Point(7, 1)), Some(2));
+        assert_eq!(paths.cost(&Point(1, 1)), None);
+    }
+
+    #[test]
+    fn test_bfs_multi() {
+        let (grid, _) = parse_maze(MAZES[1].0);
+        let keys = grid
+            .iter()
+            .filter(|(_, c)| c.is_ascii_lowercase())
+            .map(|(point, _)| point);
+        let paths = bfs_multi(keys, |point| open_neighbors(&grid, *point));
+        let farthest = paths.iter().map(|(_, steps)| steps).max();
+        assert_eq!(farthest, Some(12));
+        assert_eq!(paths.cost(&Point(15, 1)), Some(2));
+        assert_eq!(paths.path_to(&Point(17, 1)), Some(vec![Point(17, 1)]));
+    }
+
+    #[test]
+    fn test_collect_keys() {
+        for (maze, expected) in MAZES {
+            let (grid, start) = parse_maze(maze);
+            let all_keys = grid
+                .iter()
+                .filter(|(_, c)| c.is_ascii_lowercase())
+                .fold(0u32, |keys, (_, c)| keys | key_bit(*c));
+
+            let successors = |(point, keys): &(Point<usize>, u32)| {
+                open_neighbors(&grid, *point)
+                    .into_iter()
+                    .filter_map(|next| match grid[next] {
+                        c if c.is_ascii_uppercase() && keys & key_bit(c) == 0 => None,
+                        c if c.is_ascii_lowercase() => Some(((next, keys | key_bit(c)), 1)),
+                        _ => Some(((next, *keys), 1)),
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let paths = dijkstra((start, 0), successors);
+            let steps = paths
+                .iter()
+                .filter(|((_, keys), _)| *keys == all_keys)
+                .map(|(_, steps)| steps)
+                .min();
+            assert_eq!(steps, Some(*expected));
+
+            let missing =
+                |(_, keys): &(Point<usize>, u32)| (all_keys & !keys).count_ones() as usize;
+            let (path, steps) = astar((start, 0), successors, missing, |(_, keys)| {
+                *keys == all_keys
+            })
+            .unwrap();
+            assert_eq!(steps, *expected);
+            assert_eq!(path.len(), expected + 1);
+            assert_eq!(path[0], (start, 0));
+        }
+    }
+
+    fn key_bit(c: char) -> u32 {
+        1 << (c.to_ascii_lowercase() as u32 - 'a' as u32)
+    }
+
+    #[test]
+    fn test_astar() {
+        let (grid, start) = parse_maze(MAZES[1].0);
+        let goal = Point(1, 3);
+        let successors = |point: &Point<usize>| {
+            open_neighbors(&grid, *point)
+                .into_iter()
+                .map(|point| (point, 1))
+        };
+        let heuristic = |point: &Point<usize>| manhattan_distance(point, &goal);
+        let (path, steps) = astar(start, successors, heuristic, |point| *point == goal).unwrap();
+        assert_eq!(steps, 30);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        assert_eq!(path.len(), steps + 1);
+
+        assert_eq!(
+            astar(start, successors, heuristic, |point| *point == Point(0, 0)),
+            None
+        );
+    }
+}
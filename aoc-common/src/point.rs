@@ -0,0 +1,88 @@
+use std::fmt;
+use std::ops;
+
+/// A point represents X, Y coordinates
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point<T>(pub T, pub T);
+
+impl<T> Point<T> {
+    pub fn x(&self) -> &T {
+        &self.0
+    }
+
+    pub fn y(&self) -> &T {
+        &self.1
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.0, self.1)
+    }
+}
+
+impl<T: ops::Add<Output = T>> ops::Add for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, other: Point<T>) -> Point<T> {
+        Point(self.0 + other.0, self.1 + other.1)
+    }
+}
+
+impl<T: ops::Sub<Output = T>> ops::Sub for Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, other: Point<T>) -> Point<T> {
+        Point(self.0 - other.0, self.1 - other.1)
+    }
+}
+
+/// Returns the absolute difference between two numbers. This works for
+/// unsigned numbers too.
+fn abs_diff<T: Copy + PartialOrd + ops::Sub<Output = T>>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+/// Calculates the Manhattan distance between two points
+pub fn manhattan_distance<T>(p1: &Point<T>, p2: &Point<T>) -> T
+where
+    T: Copy + PartialOrd + ops::Add<Output = T> + ops::Sub<Output = T>,
+{
+    abs_diff(p1.0, p2.0) + abs_diff(p1.1, p2.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manhattan_distance() {
+        let tests = &[
+            ((Point(0, 0), Point(0, 0)), 0),
+            ((Point(1, 1), Point(0, 0)), 2),
+            ((Point(10, 10), Point(1, 1)), 18),
+            ((Point(-3, 4), Point(2, -1)), 10),
+        ];
+        for ((p1, p2), expected) in tests {
+            assert_eq!(&manhattan_distance(p1, p2), expected);
+            assert_eq!(&manhattan_distance(p2, p1), expected);
+        }
+
+        let p1: Point<usize> = Point(0, 0);
+        let p2 = Point(6, 6);
+        assert_eq!(manhattan_distance(&p1, &p2), 12);
+        assert_eq!(manhattan_distance(&p2, &p1), 12);
+    }
+
+    #[test]
+    fn test_ops() {
+        assert_eq!(Point(1, 2) + Point(3, -4), Point(4, -2));
+        assert_eq!(Point(1, 2) - Point(3, -4), Point(-2, 6));
+        assert_eq!(Point(3, 4).to_string(), "3,4");
+        assert_eq!((Point(3, 4).x(), Point(3, 4).y()), (&3, &4));
+    }
+}
@@ -0,0 +1,23 @@
+/// Renders a `width` by `height` grid of characters, one row per line. The
+/// character at each position is returned by `cell(x, y)`.
+pub fn render<F>(width: usize, height: usize, mut cell: F) -> String
+where
+    F: FnMut(usize, usize) -> char,
+{
+    (0..height)
+        .map(|y| (0..width).map(|x| cell(x, y)).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let diagonal = render(3, 2, |x, y| if x == y { '#' } else { '.' });
+        assert_eq!(diagonal, "#..\n.#.");
+        assert_eq!(render(0, 0, |_, _| '#'), "");
+    }
+}
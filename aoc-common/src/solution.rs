@@ -0,0 +1,381 @@
+use crate::{Drawing, InputCache, ParseError};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::{error, fmt, io};
+
+/// An answer to one part of a puzzle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+    /// Text spread over several lines, like a rendered image
+    Lines(Vec<String>),
+    /// The part has no solution yet
+    Unsolved,
+}
+
+impl Answer {
+    /// Creates a multi-line answer, one line per line of `s`
+    pub fn lines(s: &str) -> Self {
+        Answer::Lines(s.lines().map(|line| line.to_string()).collect())
+    }
+
+    pub fn is_solved(&self) -> bool {
+        *self != Answer::Unsolved
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Lines(lines) => write!(f, "{}", lines.join("\n")),
+            Answer::Unsolved => write!(f, "unsolved"),
+        }
+    }
+}
+
+macro_rules! impl_from_number {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Number(n as i64)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_number!(i32, i64, isize, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    fn from(answer: Option<T>) -> Self {
+        answer.map_or(Answer::Unsolved, Into::into)
+    }
+}
+
+/// An error from solving a part of a puzzle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// The input text could not be parsed
+    Parse(ParseError),
+    /// The input parsed, but solving failed while running it, like an
+    /// Intcode program reaching an invalid instruction
+    Runtime(String),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::Parse(err) => write!(f, "invalid input: {}", err),
+            SolveError::Runtime(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl error::Error for SolveError {}
+
+impl From<ParseError> for SolveError {
+    fn from(err: ParseError) -> Self {
+        SolveError::Parse(err)
+    }
+}
+
+/// A solution to both parts of a day's puzzle. Each part takes the puzzle
+/// input as text, and fails if the input cannot be parsed or the solution
+/// fails while running it.
+pub trait Solution {
+    /// Parses the input without solving either part, so parsing can be
+    /// timed on its own
+    fn parse(&self, _input: &str) -> Result<(), ParseError> {
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError>;
+
+    fn part2(&self, _input: &str) -> Result<Answer, SolveError> {
+        Ok(Answer::Unsolved)
+    }
+
+    /// Draws the day's result, for days where there is something to look
+    /// at
+    fn draw(&self, _input: &str) -> Result<Option<Drawing>, SolveError> {
+        Ok(None)
+    }
+}
+
+/// The name of a day's puzzle input
+pub const INPUT: &str = "input";
+
+/// The name of the example input given in a day's puzzle description
+pub const EXAMPLE: &str = "example";
+
+/// A registry holds the solutions for each year and day, along with any
+/// inputs embedded in the crate. Inputs are named, like `INPUT` or
+/// `EXAMPLE`, and may have an expected output. Inputs loaded with `load`
+/// come from the input cache first, if there is one.
+#[derive(Default)]
+pub struct Registry {
+    solutions: BTreeMap<(u32, u32), Box<dyn Solution>>,
+    inputs: BTreeMap<(u32, u32, &'static str), &'static str>,
+    expected: BTreeMap<(u32, u32, &'static str), &'static str>,
+    cache: Option<InputCache>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        let solutions = BTreeMap::new();
+        let inputs = BTreeMap::new();
+        let expected = BTreeMap::new();
+        Registry {
+            solutions,
+            inputs,
+            expected,
+            cache: None,
+        }
+    }
+
+    /// Adds the solution for a day, replacing any existing solution
+    pub fn register<S: Solution + 'static>(&mut self, year: u32, day: u32, solution: S) {
+        self.solutions.insert((year, day), Box::new(solution));
+    }
+
+    /// Adds a named input for a day, replacing any existing input with the
+    /// same name
+    pub fn add_input(&mut self, year: u32, day: u32, name: &'static str, input: &'static str) {
+        self.inputs.insert((year, day, name), input);
+    }
+
+    /// Adds the expected output for a named input, like the rendered message
+    /// of a day whose answer is an image
+    pub fn add_expected(&mut self, year: u32, day: u32, name: &'static str, output: &'static str) {
+        self.expected.insert((year, day, name), output);
+    }
+
+    /// Sets the input cache that `load` looks in before the embedded inputs
+    pub fn set_cache(&mut self, cache: InputCache) {
+        self.cache = Some(cache);
+    }
+
+    pub fn cache(&self) -> Option<&InputCache> {
+        self.cache.as_ref()
+    }
+
+    pub fn get(&self, year: u32, day: u32) -> Option<&dyn Solution> {
+        self.solutions
+            .get(&(year, day))
+            .map(|solution| solution.as_ref())
+    }
+
+    pub fn input(&self, year: u32, day: u32, name: &str) -> Option<&'static str> {
+        self.inputs.get(&(year, day, name)).copied()
+    }
+
+    /// Returns the embedded expected output for a named input
+    pub fn expected(&self, year: u32, day: u32, name: &str) -> Option<&'static str> {
+        self.expected.get(&(year, day, name)).copied()
+    }
+
+    /// Returns the names of the embedded inputs for a day in order
+    pub fn input_names(&self, year: u32, day: u32) -> Vec<&'static str> {
+        self.inputs
+            .keys()
+            .filter(|(y, d, _)| (*y, *d) == (year, day))
+            .map(|(_, _, name)| *name)
+            .collect()
+    }
+
+    /// Returns a named input for a day from the input cache, or the embedded
+    /// input if it is not cached
+    pub fn load(&self, year: u32, day: u32, name: &str) -> io::Result<Option<Cow<'static, str>>> {
+        if let Some(cache) = &self.cache {
+            if let Some(input) = cache.read(year, day, name)? {
+                return Ok(Some(Cow::Owned(input)));
+            }
+        }
+        Ok(self.input(year, day, name).map(Cow::Borrowed))
+    }
+
+    /// Returns the expected output for a named input from the input cache,
+    /// or the embedded output if it is not cached
+    pub fn load_expected(
+        &self,
+        year: u32,
+        day: u32,
+        name: &str,
+    ) -> io::Result<Option<Cow<'static, str>>> {
+        if let Some(cache) = &self.cache {
+            if let Some(output) = cache.read_solution(year, day, name)? {
+                return Ok(Some(Cow::Owned(output)));
+            }
+        }
+        Ok(self.expected(year, day, name).map(Cow::Borrowed))
+    }
+
+    /// Returns the names of the cached and embedded inputs for a day in order
+    pub fn load_names(&self, year: u32, day: u32) -> io::Result<Vec<String>> {
+        let mut names = match &self.cache {
+            Some(cache) => cache.names(year, day)?,
+            None => Vec::new(),
+        };
+        names.extend(self.input_names(year, day).into_iter().map(String::from));
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+
+    /// Returns the registered (year, day) pairs in order
+    pub fn days(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.solutions.keys().cloned()
+    }
+
+    /// Returns the registered years in order
+    pub fn years(&self) -> Vec<u32> {
+        let mut years: Vec<_> = self.days().map(|(year, _)| year).collect();
+        years.dedup();
+        years
+    }
+
+    pub fn len(&self) -> usize {
+        self.solutions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.solutions.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_number;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+            let nums = input
+                .split_whitespace()
+                .map(parse_number)
+                .collect::<Result<Vec<i64>, _>>()?;
+            Ok(nums.iter().sum::<i64>().into())
+        }
+    }
+
+    #[test]
+    fn test_answer() {
+        let tests = &[
+            (Answer::from(42_usize), "42"),
+            (Answer::from(-7_i32), "-7"),
+            (Answer::from("CABDFE"), "CABDFE"),
+            (Answer::from(Some(3_u32)), "3"),
+            (Answer::from(None::<i64>), "unsolved"),
+            (Answer::lines("#..\n.#.\n"), "#..\n.#."),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(&input.to_string(), expected);
+        }
+        assert!(!Answer::Unsolved.is_solved());
+        assert!(Answer::Number(0).is_solved());
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut registry = Registry::new();
+        assert!(registry.is_empty());
+        registry.register(2019, 2, Sum);
+        registry.register(2018, 25, Sum);
+        registry.register(2019, 1, Sum);
+        assert_eq!(registry.len(), 3);
+        assert_eq!(
+            registry.days().collect::<Vec<_>>(),
+            vec![(2018, 25), (2019, 1), (2019, 2)]
+        );
+        assert_eq!(registry.years(), vec![2018, 2019]);
+
+        let solution = registry.get(2019, 1).unwrap();
+        assert_eq!(solution.part1("1 2 3"), Ok(Answer::Number(6)));
+        assert_eq!(solution.part2("1 2 3"), Ok(Answer::Unsolved));
+        let err = solution.part1("1 two 3").unwrap_err();
+        assert_eq!(err, SolveError::from(ParseError::new("a number", "two")));
+        assert_eq!(
+            err.to_string(),
+            "invalid input: expected a number, found \"two\""
+        );
+        let err = SolveError::Runtime("out of fuel".to_string());
+        assert_eq!(err.to_string(), "out of fuel");
+        assert!(registry.get(2019, 3).is_none());
+    }
+
+    #[test]
+    fn test_registry_inputs() {
+        let mut registry = Registry::new();
+        registry.register(2019, 1, Sum);
+        registry.add_input(2019, 1, INPUT, "1 2 3\n");
+        registry.add_input(2019, 1, EXAMPLE, "4 5\n");
+        registry.add_input(2019, 2, INPUT, "6\n");
+        assert_eq!(registry.input(2019, 1, INPUT), Some("1 2 3\n"));
+        assert_eq!(registry.input(2019, 1, EXAMPLE), Some("4 5\n"));
+        assert_eq!(registry.input(2019, 1, "example2"), None);
+        assert_eq!(registry.input(2018, 1, INPUT), None);
+        assert_eq!(registry.input_names(2019, 1), vec![EXAMPLE, INPUT]);
+
+        registry.add_expected(2019, 1, INPUT, "6\n");
+        assert_eq!(registry.expected(2019, 1, INPUT), Some("6\n"));
+        assert_eq!(registry.expected(2019, 1, EXAMPLE), None);
+    }
+
+    #[test]
+    fn test_registry_cache() {
+        let dir = std::env::temp_dir().join(format!("aoc-registry-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let cache = InputCache::new(&dir);
+        cache.write(2019, 1, INPUT, "7 8\n").unwrap();
+        cache.write(2019, 1, "example2", "9\n").unwrap();
+
+        let mut registry = Registry::new();
+        registry.add_input(2019, 1, INPUT, "1 2 3\n");
+        registry.add_input(2019, 1, EXAMPLE, "4 5\n");
+        registry.add_expected(2019, 1, EXAMPLE, "9\n");
+        assert_eq!(
+            registry.load(2019, 1, INPUT).unwrap().as_deref(),
+            Some("1 2 3\n")
+        );
+
+        registry.set_cache(cache);
+        assert_eq!(
+            registry.load(2019, 1, INPUT).unwrap().as_deref(),
+            Some("7 8\n")
+        );
+        assert_eq!(
+            registry.load(2019, 1, EXAMPLE).unwrap().as_deref(),
+            Some("4 5\n")
+        );
+        assert_eq!(registry.load(2019, 1, "example3").unwrap(), None);
+        assert_eq!(
+            registry.load_expected(2019, 1, EXAMPLE).unwrap().as_deref(),
+            Some("9\n")
+        );
+        assert_eq!(registry.load_expected(2019, 1, INPUT).unwrap(), None);
+        assert_eq!(
+            registry.load_names(2019, 1).unwrap(),
+            vec![EXAMPLE, "example2", INPUT]
+        );
+        assert_eq!(registry.input_names(2019, 1), vec![EXAMPLE, INPUT]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
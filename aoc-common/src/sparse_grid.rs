@@ -0,0 +1,199 @@
+use crate::{Grid, Point};
+use std::collections::HashMap;
+
+/// A sparse grid stores only the occupied cells of an unbounded grid. Every
+/// other cell has the default value. Coordinates may be negative.
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point<i64>, T>,
+    default: T,
+    /// The top-left and bottom-right corners of the occupied cells
+    bounds: Option<(Point<i64>, Point<i64>)>,
+}
+
+impl<T: Default> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid::new(T::default())
+    }
+}
+
+impl<T> SparseGrid<T> {
+    /// Creates an empty grid, where every cell has the value `default`
+    pub fn new(default: T) -> Self {
+        let cells = HashMap::new();
+        SparseGrid {
+            cells,
+            default,
+            bounds: None,
+        }
+    }
+
+    /// Returns the value of the cell, which is the default value if the cell
+    /// is not occupied
+    pub fn get(&self, point: Point<i64>) -> &T {
+        self.cells.get(&point).unwrap_or(&self.default)
+    }
+
+    /// Returns true if the cell has been set
+    pub fn contains(&self, point: Point<i64>) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    /// Sets the value of a cell, and returns its previous value if it was
+    /// occupied
+    pub fn insert(&mut self, point: Point<i64>, value: T) -> Option<T> {
+        self.bounds = Some(extend(self.bounds, point));
+        self.cells.insert(point, value)
+    }
+
+    /// Clears a cell back to the default value, and returns its previous
+    /// value if it was occupied
+    pub fn remove(&mut self, point: Point<i64>) -> Option<T> {
+        let value = self.cells.remove(&point)?;
+        if let Some((min, max)) = self.bounds {
+            let on_edge =
+                point.0 == min.0 || point.0 == max.0 || point.1 == min.1 || point.1 == max.1;
+            if on_edge {
+                self.bounds = self
+                    .cells
+                    .keys()
+                    .fold(None, |bounds, point| Some(extend(bounds, *point)));
+            }
+        }
+        Some(value)
+    }
+
+    /// Returns the number of occupied cells
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns the top-left and bottom-right corners of the smallest
+    /// rectangle holding every occupied cell, or None if the grid is empty
+    pub fn bounds(&self) -> Option<(Point<i64>, Point<i64>)> {
+        self.bounds
+    }
+
+    /// Returns the occupied cells, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (Point<i64>, &T)> {
+        self.cells.iter().map(|(point, value)| (*point, value))
+    }
+
+    /// Renders the bounding box of the grid, one row per line, with the
+    /// character for each cell returned by `cell`
+    pub fn render<F: Fn(&T) -> char>(&self, cell: F) -> String {
+        let (min, max) = match self.bounds {
+            Some(bounds) => bounds,
+            None => return String::new(),
+        };
+        let width = (max.0 - min.0 + 1) as usize;
+        let height = (max.1 - min.1 + 1) as usize;
+        let mut rows = vec![vec![cell(&self.default); width]; height];
+        for (point, value) in &self.cells {
+            let Point(x, y) = *point - min;
+            rows[y as usize][x as usize] = cell(value);
+        }
+        rows.iter()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl<T: Clone> SparseGrid<T> {
+    /// Converts the bounding box of the grid to a dense grid. Returns the
+    /// dense grid along with the position of its top-left cell.
+    pub fn to_grid(&self) -> (Grid<T>, Point<i64>) {
+        let (min, max) = match self.bounds {
+            Some(bounds) => bounds,
+            None => return (Grid::new(0, 0, self.default.clone()), Point(0, 0)),
+        };
+        let width = (max.0 - min.0 + 1) as usize;
+        let height = (max.1 - min.1 + 1) as usize;
+        let mut grid = Grid::new(width, height, self.default.clone());
+        for (point, value) in &self.cells {
+            let Point(x, y) = *point - min;
+            grid[Point(x as usize, y as usize)] = value.clone();
+        }
+        (grid, min)
+    }
+}
+
+impl<T: Clone + PartialEq> SparseGrid<T> {
+    /// Converts a dense grid to a sparse grid, storing only the cells which
+    /// are not `default`
+    pub fn from_grid(grid: &Grid<T>, default: T) -> Self {
+        let mut sparse = SparseGrid::new(default);
+        for (Point(x, y), value) in grid.iter() {
+            if *value != sparse.default {
+                sparse.insert(Point(x as i64, y as i64), value.clone());
+            }
+        }
+        sparse
+    }
+}
+
+/// Returns the bounds grown to include the point
+fn extend(bounds: Option<(Point<i64>, Point<i64>)>, point: Point<i64>) -> (Point<i64>, Point<i64>) {
+    match bounds {
+        None => (point, point),
+        Some((min, max)) => (
+            Point(min.0.min(point.0), min.1.min(point.1)),
+            Point(max.0.max(point.0), max.1.max(point.1)),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparse_grid() {
+        let mut grid = SparseGrid::new('.');
+        assert_eq!(grid.bounds(), None);
+        assert_eq!(grid.render(|c| *c), "");
+
+        grid.insert(Point(-2, 1), '#');
+        grid.insert(Point(1, -1), '#');
+        grid.insert(Point(0, 0), 'o');
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid.get(Point(0, 0)), &'o');
+        assert_eq!(grid.get(Point(100, -100)), &'.');
+        assert!(grid.contains(Point(-2, 1)));
+        assert!(!grid.contains(Point(-2, 0)));
+        assert_eq!(grid.bounds(), Some((Point(-2, -1), Point(1, 1))));
+        assert_eq!(grid.render(|c| *c), "...#\n..o.\n#...");
+
+        assert_eq!(grid.remove(Point(-2, 1)), Some('#'));
+        assert_eq!(grid.remove(Point(-2, 1)), None);
+        assert_eq!(grid.bounds(), Some((Point(0, -1), Point(1, 0))));
+        assert_eq!(grid.render(|c| *c), ".#\no.");
+
+        let mut occupied: Vec<_> = grid.iter().collect();
+        occupied.sort();
+        assert_eq!(occupied, vec![(Point(0, 0), &'o'), (Point(1, -1), &'#')]);
+    }
+
+    #[test]
+    fn test_convert() {
+        let mut sparse = SparseGrid::new(false);
+        sparse.insert(Point(-1, -1), true);
+        sparse.insert(Point(1, 0), true);
+        let (grid, origin) = sparse.to_grid();
+        assert_eq!(origin, Point(-1, -1));
+        assert_eq!(grid.render(|on| if *on { '#' } else { '.' }), "#..\n..#");
+
+        let sparse = SparseGrid::from_grid(&grid, false);
+        assert_eq!(sparse.len(), 2);
+        assert_eq!(sparse.bounds(), Some((Point(0, 0), Point(2, 1))));
+        assert!(*sparse.get(Point(2, 1)));
+
+        let (grid, origin) = SparseGrid::<bool>::default().to_grid();
+        assert_eq!((grid.width(), grid.height(), origin), (0, 0, Point(0, 0)));
+    }
+}
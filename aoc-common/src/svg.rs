@@ -0,0 +1,107 @@
+use crate::{Color, Point};
+use std::fmt;
+
+/// An SVG drawing, built up from shapes in puzzle coordinates. The view box
+/// grows to fit each shape, with `margin` units to spare on every side.
+/// Lines keep the same width however far the drawing is zoomed out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Svg {
+    elements: Vec<String>,
+    bounds: Option<(Point<i64>, Point<i64>)>,
+    margin: i64,
+}
+
+impl Svg {
+    pub fn new(margin: i64) -> Self {
+        Svg {
+            elements: Vec::new(),
+            bounds: None,
+            margin,
+        }
+    }
+
+    fn extend_bounds(&mut self, Point(x, y): Point<i64>) {
+        let (min, max) = self.bounds.get_or_insert((Point(x, y), Point(x, y)));
+        *min = Point(min.0.min(x), min.1.min(y));
+        *max = Point(max.0.max(x), max.1.max(y));
+    }
+
+    /// Draws a line through the points, `width` pixels wide
+    pub fn polyline(&mut self, points: &[Point<i64>], color: Color, width: u32) {
+        for point in points {
+            self.extend_bounds(*point);
+        }
+        let points: Vec<_> = points.iter().map(|point| point.to_string()).collect();
+        self.elements.push(format!(
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" \
+             vector-effect=\"non-scaling-stroke\"/>",
+            points.join(" "),
+            color.hex(),
+            width
+        ));
+    }
+
+    /// Draws a filled circle, `radius` units across
+    pub fn circle(&mut self, center: Point<i64>, radius: i64, color: Color) {
+        let Point(x, y) = center;
+        self.extend_bounds(Point(x - radius, y - radius));
+        self.extend_bounds(Point(x + radius, y + radius));
+        self.elements.push(format!(
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>",
+            x,
+            y,
+            radius,
+            color.hex()
+        ));
+    }
+
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+}
+
+impl fmt::Display for Svg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (min, max) = self.bounds.unwrap_or_default();
+        let Point(x, y) = min - Point(self.margin, self.margin);
+        let Point(width, height) = max - min + Point(2 * self.margin, 2 * self.margin);
+        writeln!(
+            f,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">",
+            x, y, width, height
+        )?;
+        for element in &self.elements {
+            writeln!(f, "  {}", element)?;
+        }
+        writeln!(f, "</svg>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svg() {
+        let mut svg = Svg::new(1);
+        assert!(svg.is_empty());
+        svg.polyline(
+            &[Point(0, 0), Point(8, 0), Point(8, -5)],
+            Color(255, 0, 0),
+            2,
+        );
+        svg.circle(Point(3, 3), 1, Color::BLACK);
+        assert_eq!(svg.len(), 2);
+
+        let expected = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-1 -6 10 11">
+  <polyline points="0,0 8,0 8,-5" fill="none" stroke="#ff0000" stroke-width="2" vector-effect="non-scaling-stroke"/>
+  <circle cx="3" cy="3" r="1" fill="#000000"/>
+</svg>
+"##;
+        assert_eq!(svg.to_string(), expected);
+    }
+}
@@ -0,0 +1,622 @@
+use aoc_common::{
+    bench, check, encode_answer, Answer, Answers, Benchmark, Check, Drawing, InputCache, Registry,
+    Report, Solution, SolveError, Status, EXAMPLE, INPUT,
+};
+use std::io::Read;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use std::{env, fs, io, process};
+
+const USAGE: &str = "\
+Usage: aoc [OPTIONS]
+
+Runs the solutions for every registered day, or only the selected ones.
+
+Options:
+  -y, --year YEAR    run the solutions for YEAR
+  -d, --day DAY      run the solution for DAY
+  -p, --part PART    run only PART (1 or 2)
+  -i, --input FILE   read the input from FILE, or from stdin if FILE is -
+  -e, --example      use the example input
+  -n, --name NAME    use the input named NAME, like example2
+  -I, --input-dir DIR
+                     look for inputs in DIR before the embedded inputs
+  -c, --check        check the answers for every named input against the
+                     known answers
+      --slow         also check the parts marked as slow
+  -b, --bench RUNS   time parsing and each part over RUNS runs
+  -r, --report FILE  write the benchmark timings to FILE as JSON
+  -w, --image FILE   draw the result of the selected day to FILE, which ends
+                     in .pbm, .pgm, .ppm, .svg or .dot
+  -s, --scale SCALE  draw each cell of the image as SCALE by SCALE pixels
+  -h, --help         print this message
+
+Inputs are read from DIR/YEAR/DAY/NAME.input, or from the embedded inputs if
+there is no such file. The input directory defaults to $AOC_INPUT_DIR, or to
+aoc in the user's cache directory.";
+
+/// The known answers, checked by `--check`
+const ANSWERS: &str = include_str!("../../answers.txt");
+
+/// Where the puzzle input is read from
+#[derive(Debug, PartialEq)]
+enum Source {
+    /// A named input for each day, from the input cache or embedded in the
+    /// crate
+    Named(String),
+    File(PathBuf),
+    Stdin,
+}
+
+#[derive(Debug, PartialEq)]
+struct Options {
+    year: Option<u32>,
+    day: Option<u32>,
+    part: Option<u32>,
+    source: Source,
+    input_dir: Option<PathBuf>,
+    check: bool,
+    slow: bool,
+    bench: Option<u32>,
+    report: Option<PathBuf>,
+    image: Option<PathBuf>,
+    scale: u32,
+    help: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            year: None,
+            day: None,
+            part: None,
+            source: Source::Named(INPUT.to_string()),
+            input_dir: None,
+            check: false,
+            slow: false,
+            bench: None,
+            report: None,
+            image: None,
+            scale: 1,
+            help: false,
+        }
+    }
+}
+
+fn parse_number(option: &str, value: Option<String>) -> Result<u32, String> {
+    let value = value.ok_or_else(|| format!("missing value for {}", option))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value for {}: {}", option, value))
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-y" | "--year" => options.year = Some(parse_number(&arg, args.next())?),
+            "-d" | "--day" => options.day = Some(parse_number(&arg, args.next())?),
+            "-p" | "--part" => match parse_number(&arg, args.next())? {
+                part @ 1..=2 => options.part = Some(part),
+                part => return Err(format!("invalid part: {}", part)),
+            },
+            "-i" | "--input" => {
+                let path = args.next().ok_or("missing value for --input")?;
+                options.source = if path == "-" {
+                    Source::Stdin
+                } else {
+                    Source::File(PathBuf::from(path))
+                };
+            }
+            "-e" | "--example" => options.source = Source::Named(EXAMPLE.to_string()),
+            "-n" | "--name" => {
+                let name = args.next().ok_or("missing value for --name")?;
+                options.source = Source::Named(name);
+            }
+            "-I" | "--input-dir" => {
+                let path = args.next().ok_or("missing value for --input-dir")?;
+                options.input_dir = Some(PathBuf::from(path));
+            }
+            "-c" | "--check" => options.check = true,
+            "--slow" => options.slow = true,
+            "-b" | "--bench" => match parse_number(&arg, args.next())? {
+                0 => return Err("invalid number of runs: 0".to_string()),
+                runs => options.bench = Some(runs),
+            },
+            "-r" | "--report" => {
+                let path = args.next().ok_or("missing value for --report")?;
+                options.report = Some(PathBuf::from(path));
+            }
+            "-w" | "--image" => {
+                let path = args.next().ok_or("missing value for --image")?;
+                if !Drawing::supports(&path) {
+                    return Err(format!("unknown image format: {}", path));
+                }
+                options.image = Some(PathBuf::from(path));
+            }
+            "-s" | "--scale" => match parse_number(&arg, args.next())? {
+                0 => return Err("invalid scale: 0".to_string()),
+                scale => options.scale = scale,
+            },
+            "-h" | "--help" => options.help = true,
+            _ => return Err(format!("unknown argument: {}", arg)),
+        }
+    }
+    let explicit = matches!(options.source, Source::File(_) | Source::Stdin);
+    if explicit && (options.year.is_none() || options.day.is_none()) {
+        return Err("an input file needs both --year and --day".to_string());
+    }
+    if explicit && options.check {
+        return Err("--check only uses the named inputs".to_string());
+    }
+    if options.check && options.bench.is_some() {
+        return Err("--check and --bench cannot be used together".to_string());
+    }
+    if options.slow && !options.check {
+        return Err("--slow needs --check".to_string());
+    }
+    if options.report.is_some() && options.bench.is_none() {
+        return Err("--report needs --bench".to_string());
+    }
+    if options.image.is_some() && (options.year.is_none() || options.day.is_none()) {
+        return Err("an image needs both --year and --day".to_string());
+    }
+    Ok(options)
+}
+
+fn registry() -> Registry {
+    let mut registry = Registry::new();
+    aoc_2018::register(&mut registry);
+    aoc_2019::register(&mut registry);
+    registry
+}
+
+/// Returns the input cache in the selected directory, or the default one
+/// if it exists
+fn input_cache(options: &Options) -> Result<Option<InputCache>, String> {
+    match &options.input_dir {
+        Some(dir) if !dir.is_dir() => Err(format!("no such directory: {}", dir.display())),
+        Some(dir) => Ok(Some(InputCache::new(dir))),
+        None => Ok(InputCache::from_env()),
+    }
+}
+
+/// Returns the name of the selected input for a day and its text. Returns
+/// None if there is no input with that name, unless a day was selected.
+fn load_input(
+    registry: &Registry,
+    options: &Options,
+    (year, day): (u32, u32),
+) -> Result<Option<(String, String)>, String> {
+    let name = match &options.source {
+        Source::Named(name) => name,
+        source => return Ok(Some(("file".to_string(), read_input(source)?))),
+    };
+    let input = registry
+        .load(year, day, name)
+        .map_err(|err| format!("could not read {} for {} day {}: {}", name, year, day, err))?;
+    match input {
+        Some(input) => Ok(Some((name.clone(), input.into_owned()))),
+        None if options.day.is_some() => Err(format!("no {} for {} day {}", name, year, day)),
+        None => Ok(None),
+    }
+}
+
+fn read_input(source: &Source) -> Result<String, String> {
+    match source {
+        Source::File(path) => fs::read_to_string(path)
+            .map_err(|err| format!("could not read {}: {}", path.display(), err)),
+        Source::Stdin => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|err| format!("could not read stdin: {}", err))?;
+            Ok(input)
+        }
+        Source::Named(_) => unreachable!(),
+    }
+}
+
+fn print_answer(part: u32, answer: &Answer, elapsed: Duration) {
+    match answer {
+        Answer::Lines(lines) => {
+            println!("  part {}: ({:?})", part, elapsed);
+            for line in lines {
+                println!("    {}", line);
+            }
+        }
+        answer => println!("  part {}: {} ({:?})", part, answer, elapsed),
+    }
+}
+
+/// Runs the selected parts of a solution, and returns the time taken
+fn run_solution(
+    solution: &dyn Solution,
+    input: &str,
+    part: Option<u32>,
+) -> Result<Duration, SolveError> {
+    let mut total = Duration::default();
+    for n in 1..=2 {
+        if part.is_some() && part != Some(n) {
+            continue;
+        }
+        let start = Instant::now();
+        let answer = if n == 1 {
+            solution.part1(input)
+        } else {
+            solution.part2(input)
+        }?;
+        let elapsed = start.elapsed();
+        print_answer(n, &answer, elapsed);
+        total += elapsed;
+    }
+    Ok(total)
+}
+
+fn print_check(check: &Check) {
+    let name = format!(
+        "{} day {} part {} {}",
+        check.year, check.day, check.part, check.input
+    );
+    match (check.status(), &check.answer) {
+        (_, Err(err)) => println!("{}: FAIL, {}", name, err),
+        (Status::Pass, _) => println!("{}: pass ({:?})", name, check.elapsed),
+        (Status::Fail, Ok(answer)) => println!(
+            "{}: FAIL, expected {}, got {} ({:?})",
+            name,
+            check.expected.as_deref().unwrap_or_default(),
+            encode_answer(answer),
+            check.elapsed
+        ),
+        (Status::Unknown, Ok(answer)) => println!(
+            "{}: unknown, got {} ({:?})",
+            name,
+            encode_answer(answer),
+            check.elapsed
+        ),
+    }
+}
+
+/// Checks the selected parts for every named input of each day against the
+/// known answers. Parts marked as slow are skipped unless `--slow` is given.
+fn run_checks(registry: &Registry, days: &[(u32, u32)], options: &Options) -> Result<(), String> {
+    let answers = Answers::parse(ANSWERS).map_err(|err| format!("answers.txt: {}", err))?;
+    let (mut passed, mut failed, mut unknown, mut skipped) = (0, 0, 0, 0);
+    for &(year, day) in days {
+        let names = registry.load_names(year, day).map_err(|err| {
+            format!(
+                "could not list the inputs for {} day {}: {}",
+                year, day, err
+            )
+        })?;
+        for input in &names {
+            for part in 1..=2 {
+                if options.part.is_some_and(|p| p != part) {
+                    continue;
+                }
+                if !options.slow && answers.is_slow(year, day, part, input) {
+                    println!(
+                        "{} day {} part {} {}: skipped, slow",
+                        year, day, part, input
+                    );
+                    skipped += 1;
+                    continue;
+                }
+                let check = check(registry, &answers, (year, day), input, part)
+                    .map_err(|err| {
+                        format!("could not read {} for {} day {}: {}", input, year, day, err)
+                    })?
+                    .unwrap();
+                print_check(&check);
+                match check.status() {
+                    Status::Pass => passed += 1,
+                    Status::Fail => failed += 1,
+                    Status::Unknown => unknown += 1,
+                }
+            }
+        }
+    }
+    println!(
+        "{} passed, {} failed, {} unknown, {} skipped",
+        passed, failed, unknown, skipped
+    );
+    if failed > 0 {
+        return Err(format!("{} checks failed", failed));
+    }
+    Ok(())
+}
+
+fn print_benchmark(benchmark: &Benchmark) {
+    let steps = std::iter::once(("parse".to_string(), &benchmark.parse)).chain(
+        benchmark
+            .parts
+            .iter()
+            .map(|(part, timing)| (format!("part {}", part), timing)),
+    );
+    for (step, timing) in steps {
+        println!(
+            "  {}: mean {:?}, min {:?}",
+            step,
+            timing.mean(),
+            timing.min()
+        );
+    }
+}
+
+/// Times the selected parts of each day over repeated runs, and writes the
+/// timings to the report file if there is one
+fn run_benches(
+    registry: &Registry,
+    days: &[(u32, u32)],
+    options: &Options,
+    runs: u32,
+) -> Result<(), String> {
+    let parts: Vec<_> = (1..=2)
+        .filter(|part| options.part.is_none_or(|p| p == *part))
+        .collect();
+    let mut report = Report::new();
+    for &(year, day) in days {
+        let (name, input) = match load_input(registry, options, (year, day))? {
+            Some(input) => input,
+            None => continue,
+        };
+        println!("{} day {} ({} runs)", year, day, runs);
+        let solution = registry.get(year, day).unwrap();
+        let benchmark = bench(solution, &input, &parts, runs as usize)
+            .map_err(|err| format!("{} day {}: {}", year, day, err))?;
+        print_benchmark(&benchmark);
+        report.add(year, day, &name, benchmark);
+    }
+    if let Some(path) = &options.report {
+        fs::write(path, report.to_string())
+            .map_err(|err| format!("could not write {}: {}", path.display(), err))?;
+    }
+    Ok(())
+}
+
+/// Draws the result of a day to the image file
+fn write_image(solution: &dyn Solution, input: &str, options: &Options) -> Result<(), String> {
+    let path = options.image.as_ref().unwrap();
+    let drawing = solution
+        .draw(input)
+        .map_err(|err| err.to_string())?
+        .ok_or("the day has no drawing")?;
+    // draw into memory first, so a drawing of the wrong kind leaves no file
+    let mut out = Vec::new();
+    drawing
+        .write(&mut out, path, options.scale as usize)
+        .and_then(|_| fs::write(path, out))
+        .map_err(|err| format!("could not write {}: {}", path.display(), err))
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let mut registry = registry();
+    if let Some(cache) = input_cache(options)? {
+        registry.set_cache(cache);
+    }
+    let days: Vec<_> = registry
+        .days()
+        .filter(|(year, day)| {
+            options.year.is_none_or(|y| y == *year) && options.day.is_none_or(|d| d == *day)
+        })
+        .collect();
+    if days.is_empty() {
+        return Err("no solutions found".to_string());
+    }
+    if options.check {
+        return run_checks(&registry, &days, options);
+    }
+    if let Some(runs) = options.bench {
+        return run_benches(&registry, &days, options, runs);
+    }
+
+    let mut total = Duration::default();
+    for (year, day) in days {
+        let input = match load_input(&registry, options, (year, day))? {
+            Some((_, input)) => input,
+            None => continue,
+        };
+        println!("{} day {}", year, day);
+        let solution = registry.get(year, day).unwrap();
+        total += run_solution(solution, &input, options.part)
+            .map_err(|err| format!("{} day {}: {}", year, day, err))?;
+        if options.image.is_some() {
+            write_image(solution, &input, options)
+                .map_err(|err| format!("{} day {}: {}", year, day, err))?;
+        }
+    }
+    println!("total: {:?}", total);
+    Ok(())
+}
+
+fn main() {
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("aoc: {}\n\n{}", err, USAGE);
+            process::exit(2);
+        }
+    };
+    if options.help {
+        println!("{}", USAGE);
+        return;
+    }
+    if let Err(err) = run(&options) {
+        eprintln!("aoc: {}", err);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Options, String> {
+        parse_args(args.split_whitespace().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let tests = &[
+            ("", Options::default()),
+            (
+                "--year 2019 -d 5 --part 2",
+                Options {
+                    year: Some(2019),
+                    day: Some(5),
+                    part: Some(2),
+                    ..Options::default()
+                },
+            ),
+            (
+                "--check -d 4",
+                Options {
+                    day: Some(4),
+                    check: true,
+                    ..Options::default()
+                },
+            ),
+            (
+                "-c --slow",
+                Options {
+                    check: true,
+                    slow: true,
+                    ..Options::default()
+                },
+            ),
+            (
+                "-b 5 -y 2018 --report bench.json",
+                Options {
+                    year: Some(2018),
+                    bench: Some(5),
+                    report: Some(PathBuf::from("bench.json")),
+                    ..Options::default()
+                },
+            ),
+            (
+                "-y 2018 -d 6 --image day6.ppm --scale 4",
+                Options {
+                    year: Some(2018),
+                    day: Some(6),
+                    image: Some(PathBuf::from("day6.ppm")),
+                    scale: 4,
+                    ..Options::default()
+                },
+            ),
+            (
+                "-y 2019 -d 3 -w day3.svg",
+                Options {
+                    year: Some(2019),
+                    day: Some(3),
+                    image: Some(PathBuf::from("day3.svg")),
+                    ..Options::default()
+                },
+            ),
+            (
+                "-y 2019 -d 6 -w day6.dot",
+                Options {
+                    year: Some(2019),
+                    day: Some(6),
+                    image: Some(PathBuf::from("day6.dot")),
+                    ..Options::default()
+                },
+            ),
+            (
+                "-y 2018 -e",
+                Options {
+                    year: Some(2018),
+                    source: Source::Named(EXAMPLE.to_string()),
+                    ..Options::default()
+                },
+            ),
+            (
+                "-y 2018 -d 6 -n example2 -I inputs",
+                Options {
+                    year: Some(2018),
+                    day: Some(6),
+                    source: Source::Named("example2".to_string()),
+                    input_dir: Some(PathBuf::from("inputs")),
+                    ..Options::default()
+                },
+            ),
+            (
+                "-y 2019 -d 1 -i input.txt",
+                Options {
+                    year: Some(2019),
+                    day: Some(1),
+                    source: Source::File(PathBuf::from("input.txt")),
+                    ..Options::default()
+                },
+            ),
+            (
+                "-y 2019 -d 1 --input -",
+                Options {
+                    year: Some(2019),
+                    day: Some(1),
+                    source: Source::Stdin,
+                    ..Options::default()
+                },
+            ),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(&parse(input).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_args_errors() {
+        let tests = &[
+            ("--day", "missing value for --day"),
+            ("--year twenty", "invalid value for --year: twenty"),
+            ("-p 3", "invalid part: 3"),
+            (
+                "-y 2019 -i input.txt",
+                "an input file needs both --year and --day",
+            ),
+            ("--verbose", "unknown argument: --verbose"),
+            (
+                "-y 2019 -d 1 -i input.txt --check",
+                "--check only uses the named inputs",
+            ),
+            ("--bench 0", "invalid number of runs: 0"),
+            ("-y 2019 -d 8 -w day8.png", "unknown image format: day8.png"),
+            ("-w day8.pbm", "an image needs both --year and --day"),
+            ("-y 2019 -d 8 -w day8.pbm -s 0", "invalid scale: 0"),
+            ("-c -b 3", "--check and --bench cannot be used together"),
+            ("-r bench.json", "--report needs --bench"),
+            ("--slow", "--slow needs --check"),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(&parse(input).unwrap_err(), expected);
+        }
+    }
+
+    #[test]
+    fn test_registry() {
+        let registry = registry();
+        assert_eq!(registry.years(), vec![2018, 2019]);
+        let solution = registry.get(2019, 1).unwrap();
+        assert_eq!(solution.part1("12\n14\n"), Ok(Answer::Number(4)));
+        assert!(registry.input(2018, 7, EXAMPLE).is_some());
+    }
+
+    #[test]
+    fn test_answers() {
+        let registry = registry();
+        let answers = Answers::parse(ANSWERS).unwrap();
+        for (year, day, _, input) in answers.keys() {
+            assert!(
+                registry.input(year, day, input).is_some(),
+                "no {} for {} day {}",
+                input,
+                year,
+                day
+            );
+        }
+        let result = check(&registry, &answers, (2019, 1), INPUT, 1)
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.status(), Status::Pass);
+        assert!(answers.is_slow(2018, 9, 2, INPUT));
+    }
+}